@@ -1,10 +1,158 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{FnArg, Ident, ItemImpl, LitInt, ReturnType, Type, parse};
+use syn::{
+    Attribute, Expr, ExprArray, ExprLit, FnArg, Ident, ItemImpl, Lit, LitInt, LitStr, Meta,
+    ReturnType, Token, Type,
+    parse::{self, Parse, ParseStream},
+};
 
-enum FuncKind {
-    None,
-    Test,
+#[derive(Default)]
+struct SuiteArgs {
+    prio: usize,
+    timeout_ms: Option<u64>,
+    on_failure: Option<Ident>,
+    abandon_on_timeout: bool,
+}
+
+impl Parse for SuiteArgs {
+    fn parse(input: ParseStream) -> parse::Result<Self> {
+        let mut args = SuiteArgs::default();
+
+        while !input.is_empty() {
+            if input.peek(LitInt) {
+                args.prio = input.parse::<LitInt>()?.base10_parse()?;
+            } else {
+                let key: Ident = input.parse()?;
+                if key == "abandon_on_timeout" {
+                    args.abandon_on_timeout = true;
+                } else {
+                    input.parse::<Token![=]>()?;
+                    match key.to_string().as_str() {
+                        "timeout" => args.timeout_ms = Some(parse_duration(&input.parse()?)?),
+                        "on_failure" => {
+                            args.on_failure = Some(parse_failure_policy(&input.parse()?)?)
+                        }
+                        _ => {
+                            return Err(parse::Error::new(
+                                key.span(),
+                                format!("unknown suite attribute `{}`", key),
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(args)
+    }
+}
+
+#[derive(Default)]
+struct TestArgs {
+    timeout_ms: Option<u64>,
+    skip: Option<LitStr>,
+    non_fatal: bool,
+    measurements: Vec<LitStr>,
+}
+
+impl TestArgs {
+    fn from_attr(attr: &Attribute) -> parse::Result<Self> {
+        let mut args = TestArgs::default();
+
+        if let Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("timeout") {
+                    args.timeout_ms = Some(parse_duration(&meta.value()?.parse()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    args.skip = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else if meta.path.is_ident("non_fatal") {
+                    args.non_fatal = true;
+                    Ok(())
+                } else if meta.path.is_ident("measurements") {
+                    args.measurements = parse_measurement_names(&meta.value()?.parse()?)?;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown test attribute"))
+                }
+            })?;
+        }
+
+        Ok(args)
+    }
+}
+
+fn parse_duration(lit: &LitStr) -> parse::Result<u64> {
+    let error = parse::Error::new(
+        lit.span(),
+        "expected a duration such as \"500ms\", \"30s\", \"2m\" or \"1h\"",
+    );
+
+    let value = lit.value();
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let Ok(number) = number.parse::<f64>() else {
+        return Err(error);
+    };
+
+    let scale = match unit.trim() {
+        "ms" => 1.0,
+        "s" => 1_000.0,
+        "m" => 60_000.0,
+        "h" => 3_600_000.0,
+        _ => return Err(error),
+    };
+
+    Ok((number * scale).round() as u64)
+}
+
+fn parse_measurement_names(array: &ExprArray) -> parse::Result<Vec<LitStr>> {
+    array
+        .elems
+        .iter()
+        .map(|elem| match elem {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(name),
+                ..
+            }) => Ok(name.clone()),
+            _ => Err(parse::Error::new_spanned(
+                elem,
+                "expected a measurement name such as \"Supply Voltage\"",
+            )),
+        })
+        .collect()
+}
+
+fn parse_failure_policy(lit: &LitStr) -> parse::Result<Ident> {
+    let policy = match lit.value().as_str() {
+        "continue" => "Continue",
+        "stop_suite" => "StopSuite",
+        "stop_run" => "StopRun",
+        _ => {
+            return Err(parse::Error::new(
+                lit.span(),
+                "expected one of \"continue\", \"stop_suite\" or \"stop_run\"",
+            ));
+        }
+    };
+
+    Ok(Ident::new(policy, lit.span()))
+}
+
+fn duration_tokens(ms: Option<u64>) -> proc_macro2::TokenStream {
+    match ms {
+        Some(ms) => quote! { Some(std::time::Duration::from_millis(#ms)) },
+        None => quote! { None },
+    }
 }
 
 #[proc_macro_attribute]
@@ -17,7 +165,6 @@ pub fn tests(args: TokenStream, input: TokenStream) -> TokenStream {
 
 fn check_test_fun_sig(sig: &syn::Signature) -> bool {
     sig.constness.is_none()
-        && sig.asyncness.is_none()
         && sig.unsafety.is_none()
         && sig.abi.is_none()
         && sig.generics.params.is_empty()
@@ -43,29 +190,7 @@ fn get_suite_type(item_impl: ItemImpl) -> Ident {
 }
 
 fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStream> {
-    let mut value: usize = 0; // Default value if no input is present
-
-    if !args.is_empty() {
-        // Attempt to parse the attribute input
-        let parsed_args = syn::parse::<LitInt>(args.clone());
-
-        match parsed_args {
-            Ok(lit_int) => {
-                // Successfully parsed as LitInt, now try to convert to usize
-                match lit_int.base10_parse::<usize>() {
-                    Ok(parsed_value) => {
-                        value = parsed_value;
-                    }
-                    Err(e) => {
-                        panic!("{}", e);
-                    }
-                }
-            }
-            Err(_) => {
-                panic!("Attribute must be a usize integer literal or empty.");
-            }
-        }
-    }
+    let suite_args: SuiteArgs = syn::parse(args)?;
 
     let mut implm: ItemImpl = syn::parse(input)?;
     let suite_ident = get_suite_type(implm.clone());
@@ -74,71 +199,103 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
 
     for item in &mut implm.items {
         if let syn::ImplItem::Fn(func) = item {
-            let mut func_kind = FuncKind::None;
+            let test_attr = func
+                .attrs
+                .iter()
+                .position(|attr| attr.path().is_ident("test"));
 
-            func.attrs.retain(|attr| {
-                if attr.path().is_ident("test") {
-                    func_kind = FuncKind::Test;
-                    false
-                } else {
-                    true
-                }
-            });
-
-            match func_kind {
-                FuncKind::Test => {
-                    let error = Err(parse::Error::new(
-                        func.sig.ident.span(),
-                        "`#[test]` function must have signature `fn(&mut self, &mut SysContext) -> Result<(), TestFailure>`",
-                    ));
-
-                    let has_return_type = match &func.sig.output {
-                        ReturnType::Type(_, _) => true,
-                        _ => false,
-                    };
-
-                    if func.sig.inputs.len() != 2 {
-                        return error;
-                    }
+            if let Some(index) = test_attr {
+                let test_args = TestArgs::from_attr(&func.attrs.remove(index))?;
 
-                    let Some(first_input) = &func.sig.inputs.first() else {
-                        return error;
-                    };
+                let error = Err(parse::Error::new(
+                    func.sig.ident.span(),
+                    "`#[test]` function must have signature `[async] fn(&mut self, &mut SysContext) -> Result<(), TestFailure>`",
+                ));
 
-                    let has_self = match first_input {
-                        FnArg::Receiver(r) => r.mutability.is_some() && r.reference.is_some(),
-                        _ => false,
-                    };
+                let has_return_type = matches!(&func.sig.output, ReturnType::Type(_, _));
 
-                    if !check_test_fun_sig(&func.sig) || !has_return_type || !has_self {
-                        return error;
-                    }
+                if func.sig.inputs.len() != 2 {
+                    return error;
+                }
+
+                let Some(first_input) = &func.sig.inputs.first() else {
+                    return error;
+                };
 
-                    let ident = func.sig.ident.clone();
-                    test_functions.push(ident);
+                let has_self = match first_input {
+                    FnArg::Receiver(r) => r.mutability.is_some() && r.reference.is_some(),
+                    _ => false,
+                };
+
+                if !check_test_fun_sig(&func.sig) || !has_return_type || !has_self {
+                    return error;
                 }
 
-                _ => (),
+                let ident = func.sig.ident.clone();
+                test_functions.push((ident, func.sig.asyncness.is_some(), test_args));
             }
         }
     }
 
-    let test_entries = test_functions.iter().map(|func| {
+    let test_entries = test_functions.iter().map(|(func, is_async, args)| {
         let name = func.to_string();
-        quote! {
-            (
-                #name,
-                Box::new(|suite_dyn, context| {
-                    let any_suite_dyn: &mut dyn Any = suite_dyn;
-                    let suite = any_suite_dyn
-                        .downcast_mut::<#suite_ident>()
-                        .expect(&format!("Failed to downcast to {}", #suite_name));
-                    suite.#func(context)
-                }),
-            )
+        let timeout = duration_tokens(args.timeout_ms);
+        let skip = match &args.skip {
+            Some(reason) => quote! { Some(#reason) },
+            None => quote! { None },
+        };
+        let non_fatal = args.non_fatal;
+        let measurements = &args.measurements;
+        let config = quote! {
+            oxidehtf::TestConfig {
+                timeout: #timeout,
+                skip: #skip,
+                non_fatal_measurements: #non_fatal,
+                measurements: &[#(#measurements),*],
+            }
+        };
+
+        if *is_async {
+            quote! {
+                (
+                    #name,
+                    oxidehtf::TestFn::new_async(|suite_dyn, context| {
+                        Box::pin(async move {
+                            let any_suite_dyn: &mut dyn Any = suite_dyn;
+                            let suite = any_suite_dyn
+                                .downcast_mut::<#suite_ident>()
+                                .expect(&format!("Failed to downcast to {}", #suite_name));
+                            suite.#func(context).await
+                        })
+                    }),
+                    #config,
+                )
+            }
+        } else {
+            quote! {
+                (
+                    #name,
+                    oxidehtf::TestFn::new_sync(|suite_dyn, context| {
+                        let any_suite_dyn: &mut dyn Any = suite_dyn;
+                        let suite = any_suite_dyn
+                            .downcast_mut::<#suite_ident>()
+                            .expect(&format!("Failed to downcast to {}", #suite_name));
+                        suite.#func(context)
+                    }),
+                    #config,
+                )
+            }
         }
     });
 
+    let suite_timeout = duration_tokens(suite_args.timeout_ms);
+    let on_failure = match &suite_args.on_failure {
+        Some(policy) => quote! { Some(oxidehtf::FailurePolicy::#policy) },
+        None => quote! { None },
+    };
+
+    let abandon_on_timeout = suite_args.abandon_on_timeout;
+
     let producer_impl = quote! {
 
         impl oxidehtf::SuiteProducer for #suite_ident {
@@ -147,13 +304,23 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
                 #suite_name
             }
 
-            fn get_tests(&self) -> Vec<(&'static str, oxidehtf::DynTestFn)> {
+            fn get_suite_config(&self) -> oxidehtf::SuiteConfig {
+                oxidehtf::SuiteConfig {
+                    timeout: #suite_timeout,
+                    on_failure: #on_failure,
+                    abandon_on_timeout: #abandon_on_timeout,
+                }
+            }
+
+            fn get_tests(&self) -> Vec<(&'static str, oxidehtf::TestFn, oxidehtf::TestConfig)> {
                 use std::any::Any;
                 vec![#(#test_entries),*]
             }
         }
     };
 
+    let prio = suite_args.prio;
+
     let function_name = Ident::new(
         &format!("__suite_gen_{}", suite_name),
         proc_macro2::Span::call_site(),
@@ -166,7 +333,7 @@ fn tests_impl(args: TokenStream, input: TokenStream) -> parse::Result<TokenStrea
 
         inventory::submit!(oxidehtf::SuiteProducerGenerator {
             func: #function_name,
-            prio: #value
+            prio: #prio
         });
     };
 
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use cli_log::*;
+use oxidehtf::RunConfig;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct Suite {
+    instrument_ready: bool,
+}
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self {
+            instrument_ready: false,
+        }
+    }
+
+    #[test]
+    async fn async_test(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .dut
+            .set_via_operator_async(&mut context.text_input)
+            .await?;
+
+        oxidehtf::assert_eq!(self.instrument_ready, true);
+
+        // Stand in for an async instrument driver
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        context
+            .measurements
+            .measure("A Voltage Measurement")
+            .with_unit("Volts")
+            .in_range(0.0, 10.0)
+            .set(1.5)?;
+
+        let input = context
+            .text_input
+            .request_async("The answer is 'Test'")
+            .await?;
+
+        info!("{}", input);
+
+        oxidehtf::assert_eq!(input, "Test");
+
+        Ok(())
+    }
+
+    #[test]
+    fn sync_test(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("A String Measurement")
+            .set_str("Value")?;
+
+        Ok(())
+    }
+}
+
+#[oxidehtf::async_trait]
+impl TestLifecycle for Suite {
+    async fn setup_async(&mut self) -> color_eyre::eyre::Result<()> {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        self.instrument_ready = true;
+        Ok(())
+    }
+}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    let responder = |prompt: &str| match prompt {
+        "Enter DUT:" => Some("AsyncDUT".to_string()),
+        "The answer is 'Test'" => Some("Test".to_string()),
+        _ => None,
+    };
+
+    oxidehtf::run_tests_with(RunConfig::new().headless(responder))
+}
@@ -0,0 +1,200 @@
+use std::time::Duration;
+
+use oxidehtf::FailPrompts;
+use oxidehtf::RunConfig;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct Suite {}
+
+#[oxidehtf_macros::tests(timeout = "2s")]
+impl Suite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test(timeout = "500ms")]
+    async fn async_hang(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(())
+    }
+
+    #[test]
+    fn within_suite_timeout(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        std::thread::sleep(Duration::from_millis(100));
+        Ok(())
+    }
+
+    #[test]
+    fn blocking_hang(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        // The suite is torn down once this returns, before NextSuite starts
+        std::thread::sleep(Duration::from_secs(4));
+        Ok(())
+    }
+
+    #[test]
+    fn never_runs(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+struct NextSuite {}
+
+#[oxidehtf_macros::tests(1)]
+impl NextSuite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn still_runs(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for NextSuite {}
+
+struct PanickingSuite {
+    tests_finished: usize,
+}
+
+#[oxidehtf_macros::tests(2)]
+impl PanickingSuite {
+    fn new() -> Self {
+        Self { tests_finished: 0 }
+    }
+
+    #[test]
+    fn sync_panic(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        let reading = "OVERLOAD";
+        let _value: f64 = reading.parse().expect("Instrument returned garbage");
+        Ok(())
+    }
+
+    #[test]
+    async fn async_panic(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        panic!("Instrument went away");
+    }
+
+    #[test]
+    fn after_panics(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(self.tests_finished, 2);
+        Ok(())
+    }
+}
+
+impl TestLifecycle for PanickingSuite {
+    fn after_test(&mut self) -> color_eyre::eyre::Result<()> {
+        self.tests_finished += 1;
+        Ok(())
+    }
+}
+
+struct FixtureSuite {
+    fixture_connected: bool,
+}
+
+#[oxidehtf_macros::tests(3)]
+impl FixtureSuite {
+    fn new() -> Self {
+        Self {
+            fixture_connected: false,
+        }
+    }
+
+    #[test]
+    fn before_test_fails(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test]
+    fn after_test_fails(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        self.fixture_connected = true;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for FixtureSuite {
+    fn before_test(&mut self) -> color_eyre::eyre::Result<()> {
+        if !self.fixture_connected {
+            self.fixture_connected = true;
+            color_eyre::eyre::bail!("Fixture not connected");
+        }
+        Ok(())
+    }
+
+    fn after_test(&mut self) -> color_eyre::eyre::Result<()> {
+        color_eyre::eyre::ensure!(!self.fixture_connected, "Fixture left clamped");
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> color_eyre::eyre::Result<()> {
+        panic!("Fixture release jammed");
+    }
+}
+
+struct BrokenSetupSuite {}
+
+#[oxidehtf_macros::tests(4)]
+impl BrokenSetupSuite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn needs_setup(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for BrokenSetupSuite {
+    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
+        color_eyre::eyre::bail!("Power supply not found")
+    }
+}
+
+struct CalibrationSuite {}
+
+#[oxidehtf_macros::tests(5, on_failure = "stop_run")]
+impl CalibrationSuite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn reference_out_of_range(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(3.3, 5.0);
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_after_failure(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for CalibrationSuite {}
+
+struct FinalSuite {}
+
+#[oxidehtf_macros::tests(6)]
+impl FinalSuite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn skipped_after_stop_run(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for FinalSuite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    oxidehtf::run_tests_with(RunConfig::new().headless(FailPrompts))
+}
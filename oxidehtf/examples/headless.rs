@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use oxidehtf::Instructions;
+use oxidehtf::JsonSink;
+use oxidehtf::JunitSink;
+use oxidehtf::RunConfig;
+use oxidehtf::RunRecord;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct Suite {}
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn set_dut(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.dut.set_via_operator(&mut context.text_input)?;
+
+        context
+            .measurements
+            .measure("A Voltage Measurement")
+            .with_unit("Volts")
+            .in_range(0.0, 10.0)
+            .set(1.5)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn answered_prompt(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let input = context.text_input.request("The answer is 'Test'")?;
+
+        oxidehtf::assert_eq!(input, "Test");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unanswered_prompt(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.text_input.request("Nobody will answer this")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn timed_prompts(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let lid_closed = context
+            .text_input
+            .prompt("Is the lid closed?")
+            .instructions(
+                Instructions::new("Close the lid")
+                    .text("Press the lid down until it **clicks**.\n\nThe latch is on the left.")
+                    .ascii_art("[ lid ]=|  <- latch"),
+            )
+            .timeout(Duration::from_secs(30))
+            .default_answer("y")
+            .confirm()?;
+
+        oxidehtf::assert_eq!(lid_closed, true);
+
+        Ok(())
+    }
+
+    #[test(skip = "Fixture not wired up yet")]
+    fn not_wired(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test]
+    fn radio_variant_only(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let has_radio = context.text_input.confirm("Does the DUT have a radio?")?;
+
+        if !has_radio {
+            return context.skip("DUT variant has no radio");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn bench_setup(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let input = &mut context.text_input;
+
+        let volts = input.number("Bench supply voltage", 0.0..=30.0)?;
+        let revision = input.choose("Board revision", ["A", "B", "C"])?;
+        let ports = input.choose_many("Ports with a cable", ["USB", "UART", "JTAG"])?;
+
+        oxidehtf::assert_eq!(volts, 12.0);
+        oxidehtf::assert_eq!(revision, "B");
+        oxidehtf::assert_eq!(ports, ["USB", "JTAG"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn visual_inspection(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .text_input
+            .acknowledge("Place the DUT under the lamp")?;
+        context
+            .text_input
+            .pass_fail("Is the case free of scratches?")
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    let responder = |prompt: &str| match prompt {
+        "Enter DUT:" => Some("HeadlessDUT".to_string()),
+        "The answer is 'Test'" => Some("Test".to_string()),
+        "Does the DUT have a radio?" => Some("n".to_string()),
+        "Bench supply voltage" => Some("12".to_string()),
+        "Board revision" => Some("2".to_string()),
+        "Ports with a cable" => Some("jtag, usb".to_string()),
+        "Place the DUT under the lamp" => Some(String::new()),
+        "Is the case free of scratches?" => Some("fail: scratch by the USB port".to_string()),
+        _ => None,
+    };
+
+    let print_outcome = |record: &RunRecord| {
+        let tests = record.suites.iter().flat_map(|s| &s.tests);
+        println!("Recorded {} tests for {}", tests.count(), record.dut_id);
+        Ok(())
+    };
+
+    oxidehtf::run_tests_with(
+        RunConfig::new()
+            .headless(responder)
+            .station("bench-1")
+            .sink(JunitSink::new("reports/{station}/{dut_id}-{timestamp}.xml"))
+            .sink(JsonSink::new("reports/{station}/{dut_id}-{timestamp}.json"))
+            .sink(print_outcome),
+    )
+}
@@ -0,0 +1,54 @@
+use oxidehtf::FailPrompts;
+use oxidehtf::LimitPrecedence;
+use oxidehtf::RunConfig;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct PowerSuite {}
+
+#[oxidehtf_macros::tests]
+impl PowerSuite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test(measurements = ["Supply Voltage", "Core Rail"])]
+    fn rails(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        // The limits file replaces the limits given here
+        measurements
+            .measure("Supply Voltage")
+            .in_range(3.0, 3.6)
+            .set(3.31)?;
+        measurements
+            .measure("Core Rail")
+            .with_unit("V")
+            .set(1.802)?;
+
+        Ok(())
+    }
+
+    #[test(measurements = ["Standby Current"])]
+    fn standby(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Standby Current")
+            .with_unit("uA")
+            .set(212.0)?;
+
+        Ok(())
+    }
+}
+
+impl TestLifecycle for PowerSuite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    oxidehtf::run_tests_with(
+        RunConfig::new()
+            .headless(FailPrompts)
+            .limits_file(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/limits.toml"))
+            .limit_precedence(LimitPrecedence::File),
+    )
+}
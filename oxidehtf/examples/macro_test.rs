@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use cli_log::*;
+use oxidehtf::Instructions;
 use oxidehtf::SysContext;
 use oxidehtf::TestFailure;
 use oxidehtf::TestLifecycle;
@@ -41,6 +44,65 @@ impl Suite {
 
         Ok(())
     }
+
+    #[test]
+    fn typed_prompts(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let input = &mut context.text_input;
+
+        let fitted = input.confirm("Is the shield fitted?")?;
+        let volts = input.number("Bench supply voltage", 0.0..=30.0)?;
+        let revision = input.choose("Board revision", ["A", "B", "C"])?;
+        let ports = input.choose_many("Ports with a cable", ["USB", "UART", "JTAG"])?;
+
+        info!("{} {} {} {:?}", fitted, volts, revision, ports);
+
+        let serial = input
+            .prompt("Scan the serial number")
+            .timeout(Duration::from_secs(10))
+            .request()?;
+
+        info!("{}", serial);
+
+        Ok(())
+    }
+
+    #[test]
+    fn probe_tp3(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let instructions = Instructions::new("Probe TP3")
+            .text(indoc::indoc! {"
+                # Before measuring
+                - Power the board from the **bench supply**
+                - Set the meter to `DC V`
+
+                Hold the red probe on TP3, right of the main chip.
+            "})
+            .ascii_art(indoc::indoc! {"
+                +------------------+
+                |  [CHIP]     (*)  |  (*) = TP3
+                +------------------+
+            "})
+            .image(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/tp3.png"));
+
+        let volts = context
+            .text_input
+            .prompt("Voltage at TP3")
+            .instructions(instructions)
+            .number(0.0..=5.0)?;
+
+        info!("{}", volts);
+
+        Ok(())
+    }
+
+    #[test]
+    fn visual_inspection(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .text_input
+            .acknowledge("Place the DUT under the lamp")?;
+        context
+            .text_input
+            .pass_fail("Is the case free of scratches?")
+    }
 }
 
 impl TestLifecycle for Suite {}
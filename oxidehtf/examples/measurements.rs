@@ -0,0 +1,267 @@
+use std::time::Duration;
+
+use oxidehtf::validators::{Aggregate, AtLeast, AtMost, Custom};
+use oxidehtf::FailPrompts;
+use oxidehtf::RunConfig;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+use oxidehtf::{CsvSink, DataTypes, JsonSink, Prefix, Unit};
+
+struct Suite {}
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn typed_values(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        measurements
+            .measure("Firmware Build")
+            .in_range(1000.0, 2000.0)
+            .set_u64(1432)?;
+        measurements
+            .measure("Temperature Offset")
+            .with_unit("Celsius")
+            .in_range(-5.0, 5.0)
+            .set_i64(-2)?;
+        measurements.measure("Self Test Passed").set_bool(true)?;
+        measurements
+            .measure("Radio State")
+            .set_enum("Idle", ["Off", "Idle", "Transmitting"])?;
+        measurements
+            .measure("Boot Time")
+            .in_duration_range(Duration::ZERO, Duration::from_millis(500))
+            .set_duration(Duration::from_millis(312))?;
+        measurements
+            .measure("Serial Number")
+            .set_bytes([0xde, 0xad, 0xbe, 0xef])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn validated_values(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        measurements
+            .measure("Supply Voltage")
+            .with_unit("Volts")
+            .within_percent(3.3, 5.0)
+            .marginal_range(3.25, 3.35)
+            .set(3.28)?;
+        measurements
+            .measure("Retry Count")
+            .at_most(3.0)
+            .set_u64(1)?;
+        measurements
+            .measure("Hardware Revision")
+            .one_of(["A", "B", "C"])
+            .set_str("B")?;
+        measurements
+            .measure("MAC Address")
+            .matches(r"^([0-9A-F]{2}:){5}[0-9A-F]{2}$")
+            .set_str("00:1B:44:11:3A:B7")?;
+        measurements
+            .measure("Checksum")
+            .validate_with(Custom::new(
+                "even checksum",
+                |value: &DataTypes| match value.as_f64() {
+                    Some(v) if v % 2.0 == 0.0 => Ok(()),
+                    _ => Err(format!("found {}", value)),
+                },
+            ))
+            .set_u64(0x3f2)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn unit_conversion(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        measurements
+            .measure("Core Rail")
+            .with_unit("mV")
+            .in_range("1.71 V", "1.89 V")
+            .set(1802.0)?;
+        measurements
+            .measure("Reference Clock")
+            .with_unit(Unit::Hertz.prefixed(Prefix::Mega))
+            .in_range((24_999.0, Unit::Hertz.prefixed(Prefix::Kilo)), "25.001 MHz")
+            .set(25.0002)?;
+        measurements
+            .measure("Standby Current")
+            .with_unit(Unit::Ampere.prefixed(Prefix::Micro))
+            .at_most((0.5, Unit::Ampere.prefixed(Prefix::Milli)))
+            .set(212.0)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn marginal_value(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Crystal Offset")
+            .with_unit("ppm")
+            .in_range(-20.0, 20.0)
+            .marginal_range(-10.0, 10.0)
+            .set(14.2)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn validator_fails(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Leakage Current")
+            .with_unit("Amps")
+            .at_least(0.0)
+            .at_most(0.001)
+            .set(0.004)?;
+
+        Ok(())
+    }
+
+    #[test(non_fatal)]
+    fn rail_sweep(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        for (rail, nominal, volts) in [("1V8", 1.8, 1.79), ("3V3", 3.3, 3.61), ("5V0", 5.0, 5.02)] {
+            context
+                .measurements
+                .measure(format!("Rail {}", rail))
+                .with_unit("Volts")
+                .within_percent(nominal, 5.0)
+                .set(volts)?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn optional_check(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        measurements
+            .measure("Fan Speed")
+            .with_unit("RPM")
+            .at_least(1200.0)
+            .non_fatal()
+            .set(950.0)?;
+        measurements
+            .measure("Board Temperature")
+            .with_unit("Celsius")
+            .in_range(10.0, 60.0)
+            .set(41.0)?;
+
+        Ok(())
+    }
+
+    #[test(measurements = ["Link Quality", "Antenna Gain"])]
+    fn declared_not_set(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.measurements.declare("Noise Floor");
+
+        context
+            .measurements
+            .measure("Link Quality")
+            .at_least(0.8)
+            .set(0.93)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn frequency_sweep(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let points: Vec<[f64; 2]> = [1e3, 1e4, 1e5, 1e6, 1e7, 1e8]
+            .into_iter()
+            .map(|hz: f64| [hz, -0.4 * (hz / 1e6).log10().max(0.0) - 0.1])
+            .collect();
+
+        context
+            .measurements
+            .measure("Gain")
+            .with_axes([("Frequency", "Hz"), ("Gain", "dB")])
+            .each_point(AtLeast(-3.0))
+            .validate_with(Aggregate::max(AtMost(0.5)))
+            .set_rows(points)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn calibrated_values(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let measurements = &mut context.measurements;
+
+        measurements
+            .measure("Load Current")
+            .with_unit("Amps")
+            .scale(1.02)
+            .offset(-0.003)
+            .in_range(0.45, 0.55)
+            .set(0.49)?;
+        measurements
+            .measure("Bandgap Reference")
+            .convert_unit("mV", "V")
+            .within_percent(1.2, 1.0)
+            .set(1203.5)?;
+        measurements
+            .measure("Ripple")
+            .with_unit("mV")
+            .mean()
+            .at_most(15.0)
+            .set_samples([11.2, 12.8, 10.9, 13.4, 12.1])?;
+        measurements
+            .measure("Output Power")
+            .with_unit("dBm")
+            .map("watts to dBm", |value| {
+                let watts = value.as_f64().ok_or("expected watts")?;
+                Ok(DataTypes::F64(10.0 * (watts * 1000.0).log10()))
+            })
+            .in_range(18.0, 22.0)
+            .set(0.1)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn sampled_values(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let noise = [0.82, 0.91, 0.77, 0.88, 0.95, 0.79, 0.86, 0.90];
+
+        context
+            .measurements
+            .measure("Output Noise")
+            .with_unit("mV")
+            .validate_with(Aggregate::mean(AtMost(1.0)))
+            .validate_with(Aggregate::max(AtMost(1.2)))
+            .validate_with(Aggregate::stddev(AtMost(0.1)))
+            .set_samples(&noise[..])?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn enum_not_allowed(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Power Mode")
+            .set_enum("Turbo", ["Sleep", "Normal"])?;
+
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    oxidehtf::run_tests_with(
+        RunConfig::new()
+            .headless(FailPrompts)
+            .sink(JsonSink::new("run-record.json"))
+            .sink(CsvSink::new("measurements.csv")),
+    )
+}
@@ -0,0 +1,67 @@
+use oxidehtf::FailPrompts;
+use oxidehtf::RunConfig;
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct Suite {}
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test]
+    fn set_dut(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let serial = context
+            .text_input
+            .prompt("Scan the DUT serial")
+            .id("dut-serial")
+            .request()?;
+        context.dut.set_id(serial)
+    }
+
+    #[test]
+    fn fixture(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let closed = context.text_input.confirm("Is the fixture closed?")?;
+
+        oxidehtf::assert_eq!(closed, true);
+
+        Ok(())
+    }
+
+    #[test]
+    fn bench_setup(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let input = &mut context.text_input;
+
+        let volts = input.number("Bench supply voltage", 0.0..=30.0)?;
+        let revision = input.choose("Board revision", ["A", "B", "C"])?;
+
+        oxidehtf::assert_eq!(volts, 12.0);
+        oxidehtf::assert_eq!(revision, "B");
+
+        Ok(())
+    }
+
+    #[test]
+    fn unscripted(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        // The script runs out of answers, failing this test
+        context.text_input.request("Anything else?")?;
+
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    oxidehtf::run_tests_with(
+        RunConfig::new()
+            .headless(FailPrompts)
+            .response_script(concat!(
+                env!("CARGO_MANIFEST_DIR"),
+                "/examples/responses.toml"
+            )),
+    )
+}
@@ -5,7 +5,8 @@ use std::any::Any;
 use cli_log::*;
 use color_eyre::eyre::Result;
 use oxidehtf::{
-    DynTestFn, SuiteProducer, SuiteProducerGenerator, SysContext, TestFailure, TestLifecycle,
+    SuiteProducer, SuiteProducerGenerator, SysContext, TestConfig, TestFailure, TestFn,
+    TestLifecycle,
 };
 
 struct Suite {}
@@ -52,29 +53,31 @@ impl SuiteProducer for Suite {
         "suite1"
     }
 
-    fn get_tests(&self) -> Vec<(&'static str, DynTestFn)> {
-        let mut tests: Vec<(&'static str, DynTestFn)> = Vec::new();
+    fn get_tests(&self) -> Vec<(&'static str, TestFn, TestConfig)> {
+        let mut tests: Vec<(&'static str, TestFn, TestConfig)> = Vec::new();
 
         tests.push((
             "test1",
-            Box::new(|suite_dyn, context| {
+            TestFn::new_sync(|suite_dyn, context| {
                 let any_suite_dyn: &mut dyn Any = suite_dyn;
                 let suite = any_suite_dyn
                     .downcast_mut::<Suite>()
                     .expect("Failed to downcast suite to Suite");
                 suite.test1(context)
             }),
+            TestConfig::default(),
         ));
 
         tests.push((
             "test2",
-            Box::new(|suite_dyn, context| {
+            TestFn::new_sync(|suite_dyn, context| {
                 let any_suite_dyn: &mut dyn Any = suite_dyn;
                 let suite = any_suite_dyn
                     .downcast_mut::<Suite>()
                     .expect("Failed to downcast suite to Suite");
                 suite.test2(context)
             }),
+            TestConfig::default(),
         ));
 
         tests
@@ -1,13 +1,15 @@
-use tokio::sync::oneshot;
 use tui_input::InputRequest;
 
-use crate::ui::Screens;
+use crate::{
+    test_runner::context::user_text_input::prompt::{AnswerSender, Prompt},
+    ui::Screens,
+};
 
 #[derive(Debug)]
 pub enum Action {
     SendInput,
     ExitApp,
-    UserInputPrompt(String, Option<oneshot::Sender<String>>),
+    OperatorPrompt(Prompt, Option<AnswerSender>),
     FocusNextPane,
     FocusPreviousPane,
     MoveUp,
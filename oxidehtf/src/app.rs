@@ -2,10 +2,12 @@ use std::collections::VecDeque;
 
 use crate::{
     common::*,
-    test_runner::{
-        data::suite::SuiteDataCollection, SuiteData, SuiteProducer, SuiteProducerGenerator,
-        TestDone, TestRunner, TestState,
-    },
+    config::RunConfig,
+    limits::LimitsFile,
+    record::RunRecord,
+    report::{self, ReportSink},
+    script::ResponseScript,
+    test_runner::{collect_suites, data::suite::SuiteDataCollection, TestRunner},
     ui::{Screens, Ui},
 };
 use crossterm::event::{KeyCode, KeyModifiers};
@@ -23,6 +25,7 @@ pub enum Id {
     WelcomeSuites,
     RunningSuiteProgress,
     RunningTextInput,
+    RunningInstructions,
     RunningCurrentTest,
     RunningCompletedTests,
     RunningWaitingTests,
@@ -36,38 +39,40 @@ pub struct App {
     actions: VecDeque<Action>,
     to_test_runner_tx: UnboundedSender<Action>,
     event_rx: UnboundedReceiver<Event>,
+    sinks: Vec<Box<dyn ReportSink>>,
+    script: Option<ResponseScript>,
 }
 
 impl App {
-    pub fn new() -> Result<Self> {
+    pub fn new(config: RunConfig) -> Result<Self> {
         let (event_tx, event_rx) = unbounded_channel();
         let (to_test_runner_tx, to_test_runner_rx) = unbounded_channel();
 
-        let mut builders = inventory::iter::<SuiteProducerGenerator>
-            .into_iter()
-            .collect::<Vec<&SuiteProducerGenerator>>();
+        let (data, executors) = collect_suites();
 
-        builders.sort_by(|a, b| a.prio.cmp(&b.prio));
+        let limits = config.load_limits(&data)?;
+        let script = config.load_script()?;
 
-        let (data, executors): (Vec<SuiteData>, Vec<Box<dyn SuiteProducer>>) = builders
-            .iter()
-            .map(|p| {
-                let executor = (p.func)();
-                let names = executor.get_tests().iter().map(|t| t.0).collect();
-                (
-                    SuiteData::new(names, executor.get_suite_name(), p.prio),
-                    executor,
-                )
-            })
-            .collect();
+        let suites_collection = SuiteDataCollection::new(
+            data,
+            config.station.clone(),
+            limits.as_ref().map(LimitsFile::record),
+            event_tx.clone(),
+        );
 
-        let suites_collection = SuiteDataCollection::new(data, event_tx.clone());
+        let sinks = match config.sinks {
+            sinks if sinks.is_empty() => report::default_sinks(),
+            sinks => sinks,
+        };
 
         let test_runner = TestRunner::new(
             executors,
             suites_collection.clone(),
             event_tx.clone(),
             to_test_runner_rx,
+            config.failure_policy,
+            limits,
+            config.limit_precedence,
         );
 
         Ok(Self {
@@ -78,19 +83,21 @@ impl App {
             actions: VecDeque::new(),
             to_test_runner_tx,
             event_rx,
+            sinks,
+            script,
         })
     }
 
     pub async fn run(&mut self) -> Result<()> {
         self.ui.start();
         let mut test_runner = self.test_runner.take().ok_or_eyre("No Test Runner")?;
-        let mut runner_handle = tokio::task::spawn_blocking(move || test_runner.run());
+        let mut runner_handle = tokio::spawn(async move { test_runner.run().await });
         let mut is_runner_done = false;
 
         while self.state() != AppState::Done {
             self.handle_event().await?;
             self.handle_actions().await?;
-            let state = self.suites_data.get_raw_copy().await;
+            let state = self.suites_data.get_raw_copy();
             self.ui.render(state)?;
 
             if !is_runner_done {
@@ -108,7 +115,8 @@ impl App {
             }
         }
 
-        self.produce_junit_report().await?;
+        let data = self.suites_data.get_raw_copy();
+        report::write_reports(&mut self.sinks, &RunRecord::new(&data))?;
 
         Ok(())
     }
@@ -125,14 +133,20 @@ impl App {
                 (KeyModifiers::SHIFT, KeyCode::BackTab) => Some(Action::FocusPreviousPane),
                 _ => None,
             },
-            Event::UserInputPrompt(ref s, ref mut c) => {
-                let channel = c.take();
-                Some(Action::UserInputPrompt(s.clone(), channel))
-            }
+            Event::OperatorPrompt(ref s, ref mut c) => match &mut self.script {
+                Some(script) => {
+                    let _ = script.reply(s, c.take());
+                    return Ok(());
+                }
+                None => {
+                    let channel = c.take();
+                    Some(Action::OperatorPrompt(s.clone(), channel))
+                }
+            },
             Event::CurrentSuiteDut(ref s) => Some(Action::SetCurrentSuiteDut(s.clone())),
             Event::TestsCompleted => Some(Action::ChangeScreen(Screens::Summary)),
             // Used to update UI
-            Event::NOP => return Ok(()),
+            Event::Nop => return Ok(()),
             _ => None,
         };
 
@@ -162,14 +176,10 @@ impl App {
                     }
                     self.ui.active(s);
                 }
-                SetCurrentSuiteDut(ref s) => {
-                    self.suites_data
-                        .write(|d| {
-                            d.dut_id = s.clone();
-                            Ok(())
-                        })
-                        .await?
-                }
+                SetCurrentSuiteDut(ref s) => self.suites_data.write(|d| {
+                    d.dut_id = s.clone();
+                    Ok(())
+                })?,
                 _ => (),
             }
 
@@ -259,37 +269,4 @@ impl App {
 
     //     Ok(())
     // }
-
-    async fn produce_junit_report(&self) -> Result<()> {
-        use quick_junit::{NonSuccessKind, Report, TestCase, TestCaseStatus, TestSuite};
-
-        let mut report = Report::new("htf2-run");
-
-        for suite in &self.suites_data.data.read().await.inner {
-            let mut test_suite = TestSuite::new(format!("{}", suite.name));
-
-            for test in &suite.test_data {
-                let test_case_result = match &test.state {
-                    TestState::Done(r) => match r {
-                        TestDone::Passed => TestCaseStatus::success(),
-                        TestDone::Failed(_) => TestCaseStatus::non_success(NonSuccessKind::Failure),
-                    },
-
-                    _ => TestCaseStatus::non_success(NonSuccessKind::Error),
-                };
-                let mut test_case = TestCase::new(test.name, test_case_result);
-                test_case.set_time(test.duration);
-                test_suite.add_test_case(test_case);
-            }
-
-            report.add_test_suite(test_suite);
-            report.timestamp = Some(suite.start_time);
-        }
-
-        let junit_file = std::fs::File::create("junit-report.xml")?;
-
-        report.serialize(junit_file)?;
-
-        Ok(())
-    }
 }
@@ -2,5 +2,5 @@ pub use crate::{actions::Action, events::Event};
 pub use cli_log::*;
 pub use color_eyre::eyre::{eyre, OptionExt, Result};
 pub use std::sync::Arc;
+pub use std::sync::RwLock;
 pub use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
-pub use tokio::sync::RwLock;
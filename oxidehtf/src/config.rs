@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use crate::{
+    common::*,
+    headless::PromptResponder,
+    limits::{LimitPrecedence, LimitsFile},
+    record::StationInfo,
+    report::ReportSink,
+    script::ResponseScript,
+    test_runner::{FailurePolicy, SuiteData},
+};
+
+/// Options used to start a test run, see [`crate::run_tests_with`].
+#[derive(Default)]
+pub struct RunConfig {
+    pub(crate) responder: Option<Box<dyn PromptResponder>>,
+    pub(crate) failure_policy: FailurePolicy,
+    pub(crate) station: StationInfo,
+    pub(crate) sinks: Vec<Box<dyn ReportSink>>,
+    pub(crate) limits: Option<PathBuf>,
+    pub(crate) limit_precedence: LimitPrecedence,
+    pub(crate) script: Option<PathBuf>,
+}
+
+impl RunConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run without the TUI, answering operator prompts with `responder` and
+    /// printing progress to stdout.
+    pub fn headless(mut self, responder: impl PromptResponder + 'static) -> Self {
+        self.responder = Some(Box::new(responder));
+        self
+    }
+
+    /// Sets what happens after a test fails, suites can override this with
+    /// [`crate::SuiteConfig::on_failure`].
+    pub fn failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Names the station in the run record, defaults to the hostname.
+    pub fn station(mut self, name: impl Into<String>) -> Self {
+        self.station.name = name.into();
+        self
+    }
+
+    /// Adds a sink that receives the run record at the end of the run. Once
+    /// any sink is added the default `junit-report.xml` and `run-record.json`
+    /// outputs are no longer written, add [`crate::JunitSink`] or
+    /// [`crate::JsonSink`] to keep them.
+    pub fn sink(mut self, sink: impl ReportSink + 'static) -> Self {
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// Reads measurement limits from a TOML or JSON file, see
+    /// [`crate::LimitsFile`]. The run doesn't start if the file can't be read or
+    /// names a measurement that doesn't exist.
+    pub fn limits_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.limits = Some(path.into());
+        self
+    }
+
+    /// Sets which limits win when a measurement has limits in both the limits
+    /// file and the test code, the file by default.
+    pub fn limit_precedence(mut self, precedence: LimitPrecedence) -> Self {
+        self.limit_precedence = precedence;
+        self
+    }
+
+    /// Answers operator prompts from a TOML or JSON file instead of asking the
+    /// operator, see [`crate::ResponseScript`]. A prompt the script can't
+    /// answer fails its test.
+    pub fn response_script(mut self, path: impl Into<PathBuf>) -> Self {
+        self.script = Some(path.into());
+        self
+    }
+
+    pub(crate) fn load_script(&self) -> Result<Option<ResponseScript>> {
+        let Some(path) = &self.script else {
+            return Ok(None);
+        };
+
+        let script = ResponseScript::load(path)?;
+        info!("Loaded response script {}", path.display());
+
+        Ok(Some(script))
+    }
+
+    pub(crate) fn load_limits(&self, suites: &[SuiteData]) -> Result<Option<LimitsFile>> {
+        let Some(path) = &self.limits else {
+            return Ok(None);
+        };
+
+        let limits = LimitsFile::load(path)?;
+        limits.check(suites)?;
+        info!(
+            "Loaded limits file {} ({})",
+            path.display(),
+            limits.sha256()
+        );
+
+        Ok(Some(limits))
+    }
+}
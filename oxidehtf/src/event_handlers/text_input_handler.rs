@@ -24,6 +24,6 @@ impl TextInputHandler {
             (_, _) => None,
         };
 
-        request.map(|v| Action::UserKeyInputRequest(v))
+        request.map(Action::UserKeyInputRequest)
     }
 }
@@ -1,13 +1,14 @@
 use crossterm::event::{KeyEvent, MouseEvent};
-use tokio::sync::oneshot;
+
+use crate::test_runner::context::user_text_input::prompt::{AnswerSender, Prompt};
 
 pub enum Event {
-    NOP,
+    Nop,
     Key(KeyEvent),
     Mouse(MouseEvent),
     Paste(String),
     UpdatedTestData,
     TestsCompleted,
-    UserInputPrompt(String, Option<oneshot::Sender<String>>),
+    OperatorPrompt(Prompt, Option<AnswerSender>),
     CurrentSuiteDut(String),
 }
@@ -0,0 +1,280 @@
+use crate::{
+    common::*,
+    config::RunConfig,
+    limits::LimitsFile,
+    record::RunRecord,
+    report::{self, ReportSink},
+    script::ResponseScript,
+    test_runner::{
+        collect_suites,
+        context::user_text_input::{
+            instructions::Instructions,
+            prompt::{Answer, AnswerSender, Prompt},
+        },
+        data::suite::SuiteDataCollection,
+        SuiteDataCollectionRaw, TestDone, TestRunner, TestState,
+    },
+    TestFailure,
+};
+
+/// Answers operator prompts when running without the TUI.
+pub trait PromptResponder: Send {
+    /// Returns the answer to `prompt`, or `None` to fail the prompt.
+    fn respond(&mut self, prompt: &str) -> Option<String>;
+}
+
+impl<F> PromptResponder for F
+where
+    F: FnMut(&str) -> Option<String> + Send,
+{
+    fn respond(&mut self, prompt: &str) -> Option<String> {
+        self(prompt)
+    }
+}
+
+/// Fails every prompt, for suites that are expected to run unattended.
+pub struct FailPrompts;
+
+impl PromptResponder for FailPrompts {
+    fn respond(&mut self, _prompt: &str) -> Option<String> {
+        None
+    }
+}
+
+pub struct HeadlessApp {
+    responder: Box<dyn PromptResponder>,
+    suites_data: SuiteDataCollection,
+    test_runner: Option<TestRunner>,
+    to_test_runner_tx: UnboundedSender<Action>,
+    event_rx: UnboundedReceiver<Event>,
+    sinks: Vec<Box<dyn ReportSink>>,
+    reported_states: Vec<Vec<TestState>>,
+    script: Option<ResponseScript>,
+}
+
+impl HeadlessApp {
+    pub fn new(responder: Box<dyn PromptResponder>, config: RunConfig) -> Result<Self> {
+        let (event_tx, event_rx) = unbounded_channel();
+        let (to_test_runner_tx, to_test_runner_rx) = unbounded_channel();
+
+        let (data, executors) = collect_suites();
+
+        let reported_states = data
+            .iter()
+            .map(|s| s.test_data.iter().map(|t| t.state.clone()).collect())
+            .collect();
+
+        let limits = config.load_limits(&data)?;
+        let script = config.load_script()?;
+
+        let suites_collection = SuiteDataCollection::new(
+            data,
+            config.station.clone(),
+            limits.as_ref().map(LimitsFile::record),
+            event_tx.clone(),
+        );
+
+        let sinks = match config.sinks {
+            sinks if sinks.is_empty() => report::default_sinks(),
+            sinks => sinks,
+        };
+
+        let test_runner = TestRunner::new(
+            executors,
+            suites_collection.clone(),
+            event_tx,
+            to_test_runner_rx,
+            config.failure_policy,
+            limits,
+            config.limit_precedence,
+        );
+
+        Ok(Self {
+            responder,
+            suites_data: suites_collection,
+            test_runner: Some(test_runner),
+            to_test_runner_tx,
+            event_rx,
+            sinks,
+            reported_states,
+            script,
+        })
+    }
+
+    pub async fn run(&mut self) -> Result<()> {
+        let mut test_runner = self.test_runner.take().ok_or_eyre("No Test Runner")?;
+        let mut runner_handle = tokio::spawn(async move { test_runner.run().await });
+
+        self.to_test_runner_tx.send(Action::StartTests)?;
+
+        let result = loop {
+            tokio::select! {
+                Some(event) = self.event_rx.recv() => self.handle_event(event).await?,
+                result = &mut runner_handle => {
+                    // Pick up anything the runner sent before finishing
+                    while let Ok(event) = self.event_rx.try_recv() {
+                        self.handle_event(event).await?;
+                    }
+                    break result;
+                }
+            }
+        };
+
+        match result {
+            Ok(Ok(_)) => info!("Runner handle completed successfully!"),
+            Ok(Err(ref e)) => println!("Test runner failed: {:?}", e),
+            Err(ref e) => println!("Test runner failed: {:?}", e),
+        }
+
+        let data = self.suites_data.get_raw_copy();
+        Self::print_summary(&data);
+        report::write_reports(&mut self.sinks, &RunRecord::new(&data))?;
+
+        result??;
+
+        Ok(())
+    }
+
+    async fn handle_event(&mut self, event: Event) -> Result<()> {
+        match event {
+            Event::OperatorPrompt(prompt, reply) => match &mut self.script {
+                Some(script) => match script.reply(&prompt, reply) {
+                    Ok(answer) => println!("Prompt: '{}' - Scripted: '{}'", prompt, answer),
+                    Err(e) => println!("{}", e),
+                },
+                None => self.respond(prompt, reply),
+            },
+            Event::CurrentSuiteDut(id) => {
+                println!("DUT: {}", id);
+                self.suites_data.write(|d| {
+                    d.dut_id = id;
+                    Ok(())
+                })?;
+            }
+            Event::UpdatedTestData => self.print_progress(),
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    /// Answers `prompt` with the responder, falling back to its default
+    fn respond(&mut self, prompt: Prompt, reply: Option<AnswerSender>) {
+        if let Some(instructions) = &prompt.instructions {
+            Self::print_instructions(instructions);
+        }
+        let answer = self.responder.respond(&prompt.text);
+
+        // Answers are checked as they would be in the TUI, an invalid
+        // one counts as no answer
+        let answer = match answer.map(|a| (prompt.check(&a), a)) {
+            Some((Ok(checked), a)) => {
+                println!("Prompt: '{}' - Answered: '{}'", prompt, a);
+                Some(checked)
+            }
+            Some((Err(reason), a)) => {
+                println!("Prompt: '{}' - Invalid Answer '{}': {}", prompt, a, reason);
+                None
+            }
+            None => match &prompt.default {
+                Some(default) => {
+                    println!("Prompt: '{}' - Using Default '{}'", prompt, default);
+                    prompt.check(default).ok()
+                }
+                None => {
+                    println!("Prompt: '{}' - Not Answered", prompt);
+                    None
+                }
+            },
+        };
+
+        if let Some(reply) = reply {
+            let _ = reply.send(
+                answer
+                    .map(|text| Answer {
+                        text,
+                        scripted: false,
+                    })
+                    .ok_or(TestFailure::PromptUnanswered(prompt.text)),
+            );
+        }
+    }
+
+    fn print_progress(&mut self) {
+        let data = self.suites_data.get_raw_copy();
+
+        for (suite, reported) in data.inner.iter().zip(self.reported_states.iter_mut()) {
+            for (test, last_state) in suite.test_data.iter().zip(reported.iter_mut()) {
+                if test.state == *last_state {
+                    continue;
+                }
+                *last_state = test.state.clone();
+
+                match &test.state {
+                    TestState::Done(TestDone::Failed(e) | TestDone::Errored(e)) => println!(
+                        "[{}] {} - {} ({:.2?}): {}",
+                        suite.name, test.name, test.state, test.duration, e
+                    ),
+                    TestState::Done(TestDone::Skipped(reason)) => {
+                        println!(
+                            "[{}] {} - {}: {}",
+                            suite.name, test.name, test.state, reason
+                        )
+                    }
+                    TestState::Done(_) => println!(
+                        "[{}] {} - {} ({:.2?})",
+                        suite.name, test.name, test.state, test.duration
+                    ),
+                    _ => println!("[{}] {} - {}", suite.name, test.name, test.state),
+                }
+            }
+        }
+    }
+
+    fn print_instructions(instructions: &Instructions) {
+        println!("Instructions: {}", instructions.title);
+        for line in instructions.text.lines() {
+            println!("  {}", line);
+        }
+        if let Some(art) = instructions.fallback() {
+            for line in art.lines() {
+                println!("  {}", line);
+            }
+        }
+    }
+
+    fn print_summary(data: &SuiteDataCollectionRaw) {
+        let mut passed = 0;
+        let mut marginal = 0;
+        let mut failed = 0;
+        let mut errored = 0;
+        let mut skipped = 0;
+
+        println!();
+        println!("Summary");
+
+        for suite in &data.inner {
+            println!("{} - {}", suite.name, suite.outcome());
+            for test in &suite.test_data {
+                match test.state {
+                    TestState::Done(TestDone::Passed) => passed += 1,
+                    TestState::Done(TestDone::PassedMarginal) => marginal += 1,
+                    TestState::Done(TestDone::Errored(_)) => errored += 1,
+                    TestState::Done(TestDone::Skipped(_)) => skipped += 1,
+                    _ => failed += 1,
+                }
+                println!("  {} - {}", test.name, test.state);
+            }
+            for error in &suite.lifecycle_errors {
+                println!("  ! {}", error);
+            }
+        }
+
+        println!();
+        println!(
+            "{} passed, {} marginal, {} failed, {} errored, {} skipped",
+            passed, marginal, failed, errored, skipped
+        );
+        println!("Result: {}", data.outcome());
+    }
+}
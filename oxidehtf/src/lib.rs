@@ -1,19 +1,48 @@
 pub(crate) mod actions;
 pub(crate) mod app;
 pub(crate) mod common;
+pub(crate) mod config;
 pub(crate) mod event_handlers;
 pub(crate) mod events;
+pub(crate) mod headless;
+pub(crate) mod limits;
+pub(crate) mod record;
+pub(crate) mod report;
+pub(crate) mod script;
 pub(crate) mod test_runner;
 pub(crate) mod ui;
 
 use common::*;
 
-pub use test_runner::context::measurement::Unit;
+pub use async_trait::async_trait;
+pub use config::RunConfig;
+pub use headless::{FailPrompts, PromptResponder};
+pub use limits::{LimitPrecedence, LimitSpec, LimitsFile};
+pub use record::{
+    AxisRecord, FailureRecord, LimitsFileRecord, MeasurementOutcome, MeasurementRecord,
+    MeasurementValue, OperatorInputRecord, RangeRecord, RunRecord, StationInfo, StatisticsRecord,
+    SuiteRecord, TestOutcome, TestRecord, SCHEMA_VERSION,
+};
+pub use report::{CsvSink, JsonSink, JunitSink, OutputPath, ReportSink};
+pub use script::ResponseScript;
+pub use test_runner::context::measurement::transforms;
+pub use test_runner::context::measurement::unit::{
+    Limit, MeasurementUnit, Prefix, Quantity, ScaledUnit, Unit,
+};
+pub use test_runner::context::measurement::validators;
+pub use test_runner::context::measurement::{Axis, DataTypes, Statistics};
+pub use test_runner::context::user_text_input::instructions::Instructions;
+pub use test_runner::executer::DynAsyncTestFn;
 pub use test_runner::executer::DynTestFn;
+pub use test_runner::executer::{SuiteConfig, TestConfig};
+pub use test_runner::FailurePolicy;
+pub use test_runner::LifecycleHook;
+pub use test_runner::RuleFailure;
 pub use test_runner::SuiteProducer;
 pub use test_runner::SuiteProducerGenerator;
 pub use test_runner::SysContext;
 pub use test_runner::TestFailure;
+pub use test_runner::TestFn;
 pub use test_runner::TestLifecycle;
 
 #[macro_export]
@@ -45,18 +74,38 @@ macro_rules! register_tests {
 }
 
 pub fn run_tests() -> Result<()> {
+    run_tests_with(RunConfig::new())
+}
+
+pub fn run_tests_with(mut config: RunConfig) -> Result<()> {
     init_cli_log!();
 
-    let rt = tokio::runtime::Runtime::new()?;
+    // The default async lifecycle hooks block in place, which needs worker
+    // threads to hand the other tasks to
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
 
     info!("Starting");
 
-    rt.block_on(async move {
-        let mut app = app::App::new()?;
-        app.run().await
-    })?;
+    let result = rt.block_on(async move {
+        match config.responder.take() {
+            Some(responder) => {
+                let mut app = headless::HeadlessApp::new(responder, config)?;
+                app.run().await
+            }
+            None => {
+                let mut app = app::App::new(config)?;
+                app.run().await
+            }
+        }
+    });
+
+    // Tests abandoned after a timeout may still be blocking a thread, dropping
+    // the runtime would wait for them
+    rt.shutdown_background();
 
     info!("Finish");
 
-    Ok(())
+    result
 }
@@ -0,0 +1,199 @@
+//! Measurement limits kept outside of the test code.
+//!
+//! A limits file maps suite, test and measurement names to limits. It's read
+//! as JSON if the path ends in `.json` and as TOML otherwise:
+//!
+//! ```toml
+//! [PowerSuite.rail_3v3."Supply Voltage"]
+//! min = 3.2
+//! max = 3.4
+//! marginal = [3.25, 3.35]
+//! unit = "V"
+//! ```
+//!
+//! Every measurement in the file must be declared by its test, e.g.
+//! `#[test(measurements = ["Supply Voltage"])]`, which is checked before the
+//! run starts. See [`LimitPrecedence`] for how the file's limits combine with
+//! limits given in code.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::{common::*, record::LimitsFileRecord, test_runner::SuiteData};
+
+/// Which limits apply when a measurement has limits in both the limits file
+/// and the test code
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LimitPrecedence {
+    /// The file's limits replace any limits and marginal range given in code
+    #[default]
+    File,
+    /// The file's limits are only used for measurements set without limits
+    Code,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LimitSpec {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// Warning limits as `[min, max]`, like `marginal_range` in code
+    pub marginal: Option<[f64; 2]>,
+    /// The unit the limits are given in. Measurements without a unit take
+    /// this one.
+    pub unit: Option<String>,
+}
+
+type Suites = BTreeMap<String, BTreeMap<String, BTreeMap<String, LimitSpec>>>;
+
+/// Limits keyed by suite, test and measurement name, read from a TOML or
+/// JSON file
+#[derive(Debug, Clone)]
+pub struct LimitsFile {
+    path: PathBuf,
+    sha256: String,
+    suites: Suites,
+}
+
+impl LimitsFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read(path)
+            .map_err(|e| eyre!("Failed to read limits file {}: {}", path.display(), e))?;
+
+        let suites: Suites = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_slice(&contents)?,
+            _ => toml::from_str(std::str::from_utf8(&contents)?)?,
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            sha256: Sha256::digest(&contents)
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect(),
+            suites,
+        })
+    }
+
+    /// The SHA-256 of the file as read, in lowercase hex
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    pub fn spec(&self, suite: &str, test: &str, measurement: &str) -> Option<&LimitSpec> {
+        self.suites.get(suite)?.get(test)?.get(measurement)
+    }
+
+    /// The limits of every measurement of a test
+    pub(crate) fn test_specs(&self, suite: &str, test: &str) -> HashMap<String, LimitSpec> {
+        self.suites
+            .get(suite)
+            .and_then(|tests| tests.get(test))
+            .map(|specs| specs.clone().into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// Checks that every suite, test and measurement in the file exists,
+    /// listing all that don't
+    pub(crate) fn check(&self, suites: &[SuiteData]) -> Result<()> {
+        let mut unknown = Vec::new();
+
+        for (suite_name, tests) in &self.suites {
+            let Some(suite) = suites.iter().find(|s| s.name == suite_name) else {
+                unknown.push(format!("suite '{}'", suite_name));
+                continue;
+            };
+            for (test_name, specs) in tests {
+                let Some(test) = suite.test_data.iter().find(|t| t.name == test_name) else {
+                    unknown.push(format!("test '{}::{}'", suite_name, test_name));
+                    continue;
+                };
+                unknown.extend(
+                    specs
+                        .keys()
+                        .filter(|name| !test.user_data.contains_key(*name))
+                        .map(|name| {
+                            format!(
+                                "measurement '{}' in '{}::{}' (is it declared by the test?)",
+                                name, suite_name, test_name
+                            )
+                        }),
+                );
+            }
+        }
+
+        if !unknown.is_empty() {
+            return Err(eyre!(
+                "Limits file {} names unknown {}",
+                self.path.display(),
+                unknown.join(", ")
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn record(&self) -> LimitsFileRecord {
+        LimitsFileRecord {
+            path: self.path.display().to_string(),
+            sha256: self.sha256.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(toml: &str) -> LimitsFile {
+        LimitsFile {
+            path: PathBuf::from("limits.toml"),
+            sha256: String::new(),
+            suites: toml::from_str(toml).unwrap(),
+        }
+    }
+
+    fn suites() -> Vec<SuiteData> {
+        vec![SuiteData::new(
+            vec![("rail_3v3", &["Supply Voltage"])],
+            "PowerSuite",
+            0,
+        )]
+    }
+
+    #[test]
+    fn known_measurements_pass_the_check() {
+        let limits = limits("[PowerSuite.rail_3v3.\"Supply Voltage\"]\nmax = 3.4\n");
+        assert!(limits.check(&suites()).is_ok());
+    }
+
+    #[test]
+    fn unknown_names_are_all_listed() {
+        let limits = limits(
+            r#"
+[PowerSuite.rail_3v3."Core Rail"]
+max = 1.0
+
+[PowerSuite.rail_5v0.Rail]
+max = 5.5
+
+[RadioSuite.tx_power.Power]
+min = 10.0
+"#,
+        );
+
+        let error = limits.check(&suites()).unwrap_err().to_string();
+        for unknown in [
+            "measurement 'Core Rail' in 'PowerSuite::rail_3v3'",
+            "test 'PowerSuite::rail_5v0'",
+            "suite 'RadioSuite'",
+        ] {
+            assert!(error.contains(unknown), "{} is missing {}", error, unknown);
+        }
+    }
+}
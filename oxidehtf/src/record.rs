@@ -0,0 +1,685 @@
+//! A complete, serializable record of a test run.
+//!
+//! The record is written as JSON at the end of every run. Its layout is
+//! versioned by [`SCHEMA_VERSION`]: fields may be added without a version
+//! bump, as long as records without them still load, but renaming, removing
+//! or changing the meaning of a field will bump it. Readers should check
+//! `schema_version` before relying on a field. [`RunRecord::load`] upgrades
+//! older records to the current version.
+//!
+//! Version 2 renamed the `MeasurementNotInRange` failure kind to
+//! `MeasurementFailed`. Version 3 added `outcome` to the run, its suites and
+//! measurements.
+//!
+//! ```json
+//! {
+//!   "schema_version": 3,
+//!   "dut_id": "SN-0001",
+//!   "station": { "name": "bench-3", "os": "linux", "framework_version": "0.1.0" },
+//!   "start_time": "2025-01-01T12:00:00Z",
+//!   "end_time": "2025-01-01T12:00:05Z",
+//!   "limits_file": { "path": "limits/rev-b.toml", "sha256": "9f86d081884c7d65..." },
+//!   "outcome": "failed",
+//!   "suites": [{
+//!     "name": "PowerSuite",
+//!     "priority": 0,
+//!     "start_time": "2025-01-01T12:00:00Z",
+//!     "outcome": "failed",
+//!     "lifecycle_errors": [],
+//!     "tests": [{
+//!       "name": "rail_3v3",
+//!       "outcome": "failed",
+//!       "duration_secs": 0.12,
+//!       "failure": {
+//!         "kind": "MeasurementFailed",
+//!         "message": "Measurement 'Rail' at src/power.rs:42 - Expected: in range 3.2 to 3.4 - found 3.1",
+//!         "file": "src/power.rs",
+//!         "line": 42
+//!       },
+//!       "measurements": [
+//!         {
+//!           "name": "Rail",
+//!           "value": 3.1,
+//!           "raw_value": null,
+//!           "transforms": [],
+//!           "statistics": null,
+//!           "value_type": "f64",
+//!           "allowed": null,
+//!           "unit": "V",
+//!           "range": { "min": 3.2, "max": 3.4 },
+//!           "validators": ["in range 3.2 to 3.4"],
+//!           "marginal": { "min": 3.25, "max": 3.35 },
+//!           "outcome": "fail"
+//!         }
+//!       ],
+//!       "operator_inputs": [{ "prompt": "Enter DUT:", "response": "SN-0001", "timed_out": false, "scripted": false }]
+//!     }]
+//!   }]
+//! }
+//! ```
+
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    common::*,
+    test_runner::{
+        context::measurement::{hex, DataTypes, MeasurementStatus},
+        MeasurementDefinition, SuiteData, SuiteDataCollectionRaw, TestDone, TestFailure, TestState,
+    },
+};
+
+pub const SCHEMA_VERSION: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub schema_version: u32,
+    pub dut_id: String,
+    pub station: StationInfo,
+    pub start_time: DateTime<FixedOffset>,
+    pub end_time: DateTime<FixedOffset>,
+    /// The limits file the run used, see [`crate::RunConfig::limits_file`]
+    pub limits_file: Option<LimitsFileRecord>,
+    /// The worst outcome of all suites, see [`TestOutcome::aggregate`]
+    pub outcome: TestOutcome,
+    pub suites: Vec<SuiteRecord>,
+}
+
+/// Describes the machine the tests ran on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StationInfo {
+    pub name: String,
+    pub os: String,
+    pub framework_version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitsFileRecord {
+    pub path: String,
+    /// SHA-256 of the file's contents in hex, identifies the exact limits
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuiteRecord {
+    pub name: String,
+    pub priority: usize,
+    pub start_time: DateTime<FixedOffset>,
+    /// The worst outcome of its tests, `errored` if a lifecycle hook failed
+    pub outcome: TestOutcome,
+    /// Failures of the suite's setup and teardown hooks
+    pub lifecycle_errors: Vec<FailureRecord>,
+    pub tests: Vec<TestRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TestRecord {
+    pub name: String,
+    pub outcome: TestOutcome,
+    pub duration_secs: f64,
+    /// Set when the outcome is `failed` or `errored`
+    pub failure: Option<FailureRecord>,
+    /// Set when the outcome is `skipped`
+    pub skip_reason: Option<String>,
+    pub measurements: Vec<MeasurementRecord>,
+    pub operator_inputs: Vec<OperatorInputRecord>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TestOutcome {
+    Passed,
+    /// Passed with a measurement outside of its marginal limits
+    Marginal,
+    Failed,
+    Errored,
+    Skipped,
+    /// The run ended before the test finished
+    NotRun,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailureRecord {
+    /// The [`TestFailure`] variant name, e.g. `AssertionFailed`
+    pub kind: String,
+    pub message: String,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    /// Only set for panics
+    pub backtrace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeasurementRecord {
+    pub name: String,
+    /// `null` if the measurement was declared but never set
+    pub value: Option<MeasurementValue>,
+    /// The value as set, only recorded when `transforms` turned it into
+    /// `value`
+    pub raw_value: Option<MeasurementValue>,
+    /// Descriptions of the steps applied to `raw_value`, in order
+    #[serde(default)]
+    pub transforms: Vec<String>,
+    /// Summary of the samples of a `samples` measurement, taken before any
+    /// transform
+    pub statistics: Option<StatisticsRecord>,
+    /// One of `f64`, `i64`, `u64`, `bool`, `enum`, `duration` (in seconds),
+    /// `bytes`, `string`, `dimensioned` or `samples`
+    pub value_type: Option<String>,
+    /// The values an `enum` measurement may take
+    pub allowed: Option<Vec<String>>,
+    pub unit: Option<String>,
+    pub range: Option<RangeRecord>,
+    /// Descriptions of every rule the value was checked against
+    #[serde(default)]
+    pub validators: Vec<String>,
+    pub marginal: Option<RangeRecord>,
+    pub outcome: MeasurementOutcome,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeasurementOutcome {
+    Pass,
+    Marginal,
+    Fail,
+    /// No value was set
+    Unset,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MeasurementValue {
+    Bool(bool),
+    /// Non negative integers, whether measured as `i64` or `u64`
+    Unsigned(u64),
+    Integer(i64),
+    Number(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    /// Each row holds one value per axis
+    Dimensioned {
+        axes: Vec<AxisRecord>,
+        rows: Vec<Vec<f64>>,
+    },
+    Samples {
+        samples: Vec<f64>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AxisRecord {
+    pub name: String,
+    pub unit: Option<String>,
+}
+
+impl std::fmt::Display for MeasurementValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(v) => write!(f, "{}", v),
+            Self::Unsigned(v) => write!(f, "{}", v),
+            Self::Integer(v) => write!(f, "{}", v),
+            Self::Number(v) => write!(f, "{}", v),
+            Self::Text(v) => write!(f, "{}", v),
+            Self::Bytes(v) => write!(f, "{}", hex(v)),
+            Self::Dimensioned { axes, rows } => {
+                let names: Vec<&str> = axes.iter().map(|a| a.name.as_str()).collect();
+                write!(f, "{} points ({})", rows.len(), names.join(", "))
+            }
+            Self::Samples { samples } => write!(f, "{} samples", samples.len()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatisticsRecord {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    pub stddev: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeRecord {
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorInputRecord {
+    pub prompt: String,
+    /// `null` if the prompt was not answered
+    pub response: Option<String>,
+    /// The operator didn't answer in time, `response` is the default answer
+    /// if there was one
+    #[serde(default)]
+    pub timed_out: bool,
+    /// Answered by a response script rather than the operator
+    #[serde(default)]
+    pub scripted: bool,
+}
+
+impl RunRecord {
+    pub fn new(data: &SuiteDataCollectionRaw) -> Self {
+        let fixed_offset = FixedOffset::west_opt(0).unwrap();
+
+        Self {
+            schema_version: SCHEMA_VERSION,
+            dut_id: data.dut_id.clone(),
+            station: data.station.clone(),
+            start_time: data.start_time,
+            end_time: Utc::now().with_timezone(&fixed_offset),
+            limits_file: data.limits_file.clone(),
+            outcome: data.outcome(),
+            suites: data.inner.iter().map(SuiteRecord::new).collect(),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+
+    /// Loads a record previously written with [`RunRecord::save`], upgrading
+    /// it if an older version wrote it
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        let mut record: Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+
+        let version = record["schema_version"]
+            .as_u64()
+            .ok_or_else(|| eyre!("Run record has no schema version"))?;
+        if version > u64::from(SCHEMA_VERSION) {
+            return Err(eyre!(
+                "Run record schema version {} is newer than the supported version {}",
+                version,
+                SCHEMA_VERSION
+            ));
+        }
+
+        if version < 2 {
+            rename_v1_kinds(&mut record);
+        }
+        if version < 3 {
+            add_outcomes(&mut record)?;
+        }
+        record["schema_version"] = SCHEMA_VERSION.into();
+
+        Ok(serde_json::from_value(record)?)
+    }
+}
+
+/// Version 2 named a failed measurement `MeasurementFailed`, as it can
+/// break other rules than its range
+fn rename_v1_kinds(record: &mut Value) {
+    for suite in record["suites"].as_array_mut().into_iter().flatten() {
+        for error in suite["lifecycle_errors"]
+            .as_array_mut()
+            .into_iter()
+            .flatten()
+        {
+            rename_v1_kind(error);
+        }
+
+        for test in suite["tests"].as_array_mut().into_iter().flatten() {
+            if let Some(failure) = test.get_mut("failure") {
+                rename_v1_kind(failure);
+            }
+        }
+    }
+}
+
+fn rename_v1_kind(failure: &mut Value) {
+    if let Some(kind) = failure
+        .get_mut("kind")
+        .filter(|k| *k == "MeasurementNotInRange")
+    {
+        *kind = "MeasurementFailed".into();
+    }
+}
+
+/// Works out the outcomes versions before 3 didn't record. Some version 2
+/// records already have them, those are kept.
+fn add_outcomes(record: &mut Value) -> Result<()> {
+    let mut suite_outcomes = Vec::new();
+
+    for suite in record["suites"].as_array_mut().into_iter().flatten() {
+        let lifecycle_errors = suite["lifecycle_errors"].as_array().map_or(0, Vec::len);
+        let mut outcomes = vec![TestOutcome::Errored; lifecycle_errors];
+
+        for test in suite["tests"].as_array_mut().into_iter().flatten() {
+            outcomes.push(serde_json::from_value(test["outcome"].clone())?);
+
+            for measurement in test["measurements"].as_array_mut().into_iter().flatten() {
+                if measurement.get("outcome").is_none() {
+                    let outcome = v1_measurement_outcome(measurement);
+                    measurement["outcome"] = serde_json::to_value(outcome)?;
+                }
+            }
+        }
+
+        if suite.get("outcome").is_none() {
+            suite["outcome"] = serde_json::to_value(TestOutcome::aggregate(outcomes))?;
+        }
+        suite_outcomes.push(serde_json::from_value(suite["outcome"].clone())?);
+    }
+
+    if record.get("outcome").is_none() {
+        record["outcome"] = serde_json::to_value(TestOutcome::aggregate(suite_outcomes))?;
+    }
+    Ok(())
+}
+
+/// Before version 2 there were only range checks, a value outside of its
+/// range failed
+fn v1_measurement_outcome(measurement: &Value) -> MeasurementOutcome {
+    if measurement["value"].is_null() {
+        return MeasurementOutcome::Unset;
+    }
+
+    let value = measurement["value"].as_f64();
+    let min = measurement["range"]["min"].as_f64();
+    let max = measurement["range"]["max"].as_f64();
+    match (value, min, max) {
+        (Some(value), Some(min), Some(max)) if !(min..=max).contains(&value) => {
+            MeasurementOutcome::Fail
+        }
+        _ => MeasurementOutcome::Pass,
+    }
+}
+
+impl TestOutcome {
+    /// Combines outcomes into one, the worst of them wins. Skipped tests
+    /// only count when nothing else ran and no outcomes at all is a pass.
+    pub fn aggregate(outcomes: impl IntoIterator<Item = Self>) -> Self {
+        outcomes
+            .into_iter()
+            .max_by_key(|outcome| match outcome {
+                Self::Skipped => 0,
+                Self::Passed => 1,
+                Self::Marginal => 2,
+                Self::NotRun => 3,
+                Self::Failed => 4,
+                Self::Errored => 5,
+            })
+            .unwrap_or(Self::Passed)
+    }
+}
+
+impl From<&TestState> for TestOutcome {
+    fn from(state: &TestState) -> Self {
+        match state {
+            TestState::Done(TestDone::Passed) => Self::Passed,
+            TestState::Done(TestDone::PassedMarginal) => Self::Marginal,
+            TestState::Done(TestDone::Failed(_)) => Self::Failed,
+            TestState::Done(TestDone::Errored(_)) => Self::Errored,
+            TestState::Done(TestDone::Skipped(_)) => Self::Skipped,
+            _ => Self::NotRun,
+        }
+    }
+}
+
+impl std::fmt::Display for TestOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Passed => write!(f, "Passed"),
+            Self::Marginal => write!(f, "Marginal Pass"),
+            Self::Failed => write!(f, "Failed"),
+            Self::Errored => write!(f, "Errored"),
+            Self::Skipped => write!(f, "Skipped"),
+            Self::NotRun => write!(f, "Not Run"),
+        }
+    }
+}
+
+impl Default for StationInfo {
+    fn default() -> Self {
+        let name = std::env::var("HOSTNAME")
+            .or_else(|_| std::env::var("COMPUTERNAME"))
+            .or_else(|_| std::fs::read_to_string("/etc/hostname").map(|s| s.trim().to_string()))
+            .unwrap_or_else(|_| "unknown".to_string());
+
+        Self {
+            name,
+            os: std::env::consts::OS.to_string(),
+            framework_version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+impl SuiteRecord {
+    fn new(suite: &SuiteData) -> Self {
+        Self {
+            name: suite.name.to_string(),
+            priority: suite.priority,
+            start_time: suite.start_time,
+            outcome: suite.outcome(),
+            lifecycle_errors: suite
+                .lifecycle_errors
+                .iter()
+                .map(FailureRecord::new)
+                .collect(),
+            tests: suite
+                .test_data
+                .iter()
+                .map(|test| {
+                    let (failure, skip_reason) = match &test.state {
+                        TestState::Done(TestDone::Failed(e) | TestDone::Errored(e)) => {
+                            (Some(FailureRecord::new(e)), None)
+                        }
+                        TestState::Done(TestDone::Skipped(reason)) => (None, Some(reason.clone())),
+                        _ => (None, None),
+                    };
+
+                    TestRecord {
+                        name: test.name.to_string(),
+                        outcome: TestOutcome::from(&test.state),
+                        duration_secs: test.duration.as_secs_f64(),
+                        failure,
+                        skip_reason,
+                        measurements: test
+                            .user_data
+                            .iter()
+                            .map(|(name, def)| MeasurementRecord::new(name, def))
+                            .collect(),
+                        operator_inputs: test
+                            .operator_inputs
+                            .iter()
+                            .map(|input| OperatorInputRecord {
+                                prompt: input.prompt.clone(),
+                                response: input.response.clone(),
+                                timed_out: input.timed_out,
+                                scripted: input.scripted,
+                            })
+                            .collect(),
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+impl FailureRecord {
+    fn new(failure: &TestFailure) -> Self {
+        let (file, line) = failure.location().unzip();
+
+        let backtrace = match failure {
+            TestFailure::Panicked { backtrace, .. } => Some(backtrace.clone()),
+            _ => None,
+        };
+
+        Self {
+            kind: failure.kind().to_string(),
+            message: failure.to_string(),
+            file,
+            line,
+            backtrace,
+        }
+    }
+}
+
+impl MeasurementRecord {
+    fn new(name: &str, def: &MeasurementDefinition) -> Self {
+        let outcome = match def.status {
+            MeasurementStatus::Unset => MeasurementOutcome::Unset,
+            MeasurementStatus::Pass => MeasurementOutcome::Pass,
+            MeasurementStatus::Marginal => MeasurementOutcome::Marginal,
+            MeasurementStatus::Fail => MeasurementOutcome::Fail,
+        };
+
+        Self {
+            name: name.to_string(),
+            value: def.value.as_ref().map(MeasurementValue::from),
+            raw_value: def.raw_value.as_ref().map(MeasurementValue::from),
+            transforms: def.transforms.iter().map(|t| t.describe()).collect(),
+            statistics: def.statistics().map(|s| StatisticsRecord {
+                count: s.count,
+                mean: s.mean,
+                min: s.min,
+                max: s.max,
+                stddev: s.stddev,
+            }),
+            value_type: def.value.as_ref().map(|v| v.type_name().to_string()),
+            allowed: match &def.value {
+                Some(DataTypes::Enum { allowed, .. }) => Some(allowed.clone()),
+                _ => None,
+            },
+            unit: def.unit.as_ref().map(|u| u.to_string()),
+            range: def.limits().map(|(min, max)| RangeRecord { min, max }),
+            validators: def.validators.iter().map(|v| v.describe()).collect(),
+            marginal: def.marginal.map(|(min, max)| RangeRecord { min, max }),
+            outcome,
+        }
+    }
+}
+
+impl From<&DataTypes> for MeasurementValue {
+    fn from(value: &DataTypes) -> Self {
+        match value {
+            DataTypes::F64(v) => Self::Number(*v),
+            DataTypes::I64(v) => match u64::try_from(*v) {
+                Ok(v) => Self::Unsigned(v),
+                Err(_) => Self::Integer(*v),
+            },
+            DataTypes::U64(v) => Self::Unsigned(*v),
+            DataTypes::Bool(v) => Self::Bool(*v),
+            DataTypes::Enum { value, .. } => Self::Text(value.clone()),
+            DataTypes::Duration(v) => Self::Number(v.as_secs_f64()),
+            DataTypes::Bytes(v) => Self::Bytes(v.clone()),
+            DataTypes::String(s) => Self::Text(s.clone()),
+            DataTypes::Dimensioned { axes, rows } => Self::Dimensioned {
+                axes: axes
+                    .iter()
+                    .map(|axis| AxisRecord {
+                        name: axis.name.clone(),
+                        unit: axis.unit.as_ref().map(|u| u.to_string()),
+                    })
+                    .collect(),
+                rows: rows.clone(),
+            },
+            DataTypes::Samples(samples) => Self::Samples {
+                samples: samples.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_runner::{
+        context::measurement::Axis,
+        data::suite::{SuiteData, SuiteDataCollection},
+    };
+
+    #[test]
+    fn typed_values_round_trip() {
+        let (event_tx, _events) = tokio::sync::mpsc::unbounded_channel();
+        let suites = vec![SuiteData::new(vec![("test", &[])], "Suite", 0)];
+        let mut data =
+            SuiteDataCollection::new(suites, StationInfo::default(), None, event_tx).get_raw_copy();
+
+        let values = [
+            (
+                "Mode",
+                DataTypes::Enum {
+                    value: "Boost".into(),
+                    allowed: vec!["Buck".into(), "Boost".into()],
+                },
+            ),
+            ("Settle", DataTypes::Duration(Duration::from_millis(1500))),
+            ("Id", DataTypes::Bytes(vec![0xde, 0xad, 0x01])),
+            (
+                "Sweep",
+                DataTypes::Dimensioned {
+                    axes: vec![Axis::new("Frequency").with_unit("Hz"), Axis::new("Gain")],
+                    rows: vec![vec![10.0, 1.5], vec![20.0, 0.5]],
+                },
+            ),
+            ("Noise", DataTypes::Samples(vec![1.0, 2.0, 3.0])),
+        ];
+        for (name, value) in values {
+            data.inner[0].test_data[0].user_data.insert(
+                name.into(),
+                MeasurementDefinition {
+                    value: Some(value),
+                    status: MeasurementStatus::Pass,
+                    ..Default::default()
+                },
+            );
+        }
+
+        let record = RunRecord::new(&data);
+        let path =
+            std::env::temp_dir().join(format!("oxidehtf-record-{}.json", std::process::id()));
+        record.save(&path).unwrap();
+        let loaded = RunRecord::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), record);
+
+        let measurements: Vec<(&str, String, Option<&str>)> = record.suites[0].tests[0]
+            .measurements
+            .iter()
+            .map(|m| {
+                (
+                    m.name.as_str(),
+                    m.value.as_ref().unwrap().to_string(),
+                    m.value_type.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            measurements,
+            [
+                ("Mode", "Boost".into(), Some("enum")),
+                ("Settle", "1.5".into(), Some("duration")),
+                ("Id", "0xdead01".into(), Some("bytes")),
+                (
+                    "Sweep",
+                    "2 points (Frequency, Gain)".into(),
+                    Some("dimensioned")
+                ),
+                ("Noise", "3 samples".into(), Some("samples")),
+            ]
+        );
+
+        let mode = &record.suites[0].tests[0].measurements[0];
+        assert_eq!(mode.allowed, Some(vec!["Buck".into(), "Boost".into()]));
+        let MeasurementValue::Dimensioned { axes, .. } = record.suites[0].tests[0].measurements[3]
+            .value
+            .as_ref()
+            .unwrap()
+        else {
+            panic!("the sweep isn't dimensioned");
+        };
+        assert_eq!(axes[0].unit.as_deref(), Some("Hz"));
+        assert_eq!(axes[1].unit, None);
+    }
+}
@@ -0,0 +1,426 @@
+use std::{path::PathBuf, time::Duration};
+
+use crate::{
+    common::*,
+    record::{
+        FailureRecord, MeasurementOutcome, MeasurementRecord, MeasurementValue, RunRecord,
+        TestOutcome,
+    },
+};
+
+/// Receives the [`RunRecord`] once every test has finished, see
+/// [`crate::RunConfig::sink`].
+pub trait ReportSink: Send {
+    fn write(&mut self, record: &RunRecord) -> Result<()>;
+}
+
+impl<F> ReportSink for F
+where
+    F: FnMut(&RunRecord) -> Result<()> + Send,
+{
+    fn write(&mut self, record: &RunRecord) -> Result<()> {
+        self(record)
+    }
+}
+
+/// A file path that may contain `{dut_id}`, `{timestamp}` and `{station}`,
+/// filled in from the run record. Missing directories are created.
+#[derive(Debug, Clone)]
+pub struct OutputPath {
+    template: String,
+}
+
+impl OutputPath {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    pub fn render(&self, record: &RunRecord) -> PathBuf {
+        let dut_id = match record.dut_id.as_str() {
+            "" => "unknown",
+            id => id,
+        };
+        let timestamp = record.start_time.format("%Y%m%dT%H%M%SZ").to_string();
+
+        PathBuf::from(
+            self.template
+                .replace("{dut_id}", &sanitize(dut_id))
+                .replace("{timestamp}", &timestamp)
+                .replace("{station}", &sanitize(&record.station.name)),
+        )
+    }
+
+    fn create(&self, record: &RunRecord) -> Result<PathBuf> {
+        let path = self.render(record);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(path)
+    }
+}
+
+impl From<&str> for OutputPath {
+    fn from(template: &str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for OutputPath {
+    fn from(template: String) -> Self {
+        Self::new(template)
+    }
+}
+
+/// Keeps `value` to one path component, `.` and `..` would point at the
+/// directory itself or its parent
+fn sanitize(value: &str) -> String {
+    if value.chars().all(|c| c == '.') {
+        return value.replace('.', "_");
+    }
+
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Writes the run as a JUnit XML report
+pub struct JunitSink {
+    path: OutputPath,
+}
+
+impl JunitSink {
+    pub fn new(path: impl Into<OutputPath>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ReportSink for JunitSink {
+    fn write(&mut self, record: &RunRecord) -> Result<()> {
+        use quick_junit::{NonSuccessKind, Property, Report, TestCase, TestCaseStatus, TestSuite};
+
+        let failure_status = |kind, failure: &Option<FailureRecord>| {
+            let mut status = TestCaseStatus::non_success(kind);
+            if let Some(failure) = failure {
+                status
+                    .set_message(failure.message.clone())
+                    .set_type(failure.kind.clone());
+                if let Some(backtrace) = &failure.backtrace {
+                    status.set_description(backtrace.clone());
+                }
+            }
+            status
+        };
+
+        let mut report = Report::new("htf2-run");
+        report.set_timestamp(record.start_time);
+
+        for suite in &record.suites {
+            let mut test_suite = TestSuite::new(suite.name.clone());
+            test_suite.set_timestamp(suite.start_time);
+            test_suite.add_properties([
+                Property::new("dut_id", record.dut_id.clone()),
+                Property::new("station", record.station.name.clone()),
+                Property::new("outcome", suite.outcome.to_string()),
+            ]);
+            if let Some(limits) = &record.limits_file {
+                test_suite.add_properties([
+                    Property::new("limits_file", limits.path.clone()),
+                    Property::new("limits_sha256", limits.sha256.clone()),
+                ]);
+            }
+
+            for test in &suite.tests {
+                let test_case_result = match test.outcome {
+                    TestOutcome::Passed | TestOutcome::Marginal => TestCaseStatus::success(),
+                    TestOutcome::Failed => failure_status(NonSuccessKind::Failure, &test.failure),
+                    TestOutcome::Errored => failure_status(NonSuccessKind::Error, &test.failure),
+                    TestOutcome::Skipped => {
+                        let mut status = TestCaseStatus::skipped();
+                        if let Some(reason) = &test.skip_reason {
+                            status.set_message(reason.clone());
+                        }
+                        status
+                    }
+                    TestOutcome::NotRun => {
+                        let mut status = TestCaseStatus::non_success(NonSuccessKind::Error);
+                        status.set_message("Not run");
+                        status
+                    }
+                };
+                let mut test_case = TestCase::new(test.name.clone(), test_case_result);
+                test_case.set_time(Duration::from_secs_f64(test.duration_secs));
+                if test.outcome == TestOutcome::Marginal {
+                    test_case.add_property(Property::new("outcome", "marginal"));
+                }
+                if let Some(FailureRecord {
+                    file: Some(file),
+                    line: Some(line),
+                    ..
+                }) = &test.failure
+                {
+                    test_case.extra.insert("file".into(), file.into());
+                    test_case
+                        .extra
+                        .insert("line".into(), line.to_string().into());
+                }
+                for measurement in &test.measurements {
+                    test_case.add_properties(measurement_properties(measurement));
+                }
+                test_suite.add_test_case(test_case);
+            }
+
+            if !suite.lifecycle_errors.is_empty() {
+                let errors: Vec<&str> = suite
+                    .lifecycle_errors
+                    .iter()
+                    .map(|e| e.message.as_str())
+                    .collect();
+                test_suite.set_system_err(errors.join("\n"));
+            }
+
+            report.add_test_suite(test_suite);
+        }
+
+        let junit_file = std::fs::File::create(self.path.create(record)?)?;
+
+        report.serialize(junit_file)?;
+
+        Ok(())
+    }
+}
+
+fn measurement_properties(measurement: &MeasurementRecord) -> Vec<quick_junit::Property> {
+    use quick_junit::Property;
+
+    let key = |field: &str| format!("measurement.{}.{}", measurement.name, field);
+
+    let value = measurement
+        .value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_default();
+    let outcome = outcome_label(measurement.outcome);
+
+    let mut properties = vec![
+        Property::new(key("value"), value),
+        Property::new(key("outcome"), outcome),
+    ];
+    if let Some(raw_value) = &measurement.raw_value {
+        properties.push(Property::new(key("raw_value"), raw_value.to_string()));
+        properties.push(Property::new(
+            key("transforms"),
+            measurement.transforms.join(", "),
+        ));
+    }
+    if let Some(statistics) = &measurement.statistics {
+        properties.extend([
+            Property::new(key("count"), statistics.count.to_string()),
+            Property::new(key("mean"), statistics.mean.to_string()),
+            Property::new(key("min"), statistics.min.to_string()),
+            Property::new(key("max"), statistics.max.to_string()),
+            Property::new(key("stddev"), statistics.stddev.to_string()),
+        ]);
+    }
+    if let Some(unit) = &measurement.unit {
+        properties.push(Property::new(key("unit"), unit.clone()));
+    }
+    if let Some(range) = &measurement.range {
+        properties.push(Property::new(key("low"), range.min.to_string()));
+        properties.push(Property::new(key("high"), range.max.to_string()));
+    }
+    if let Some(marginal) = &measurement.marginal {
+        properties.push(Property::new(key("marginal_low"), marginal.min.to_string()));
+        properties.push(Property::new(
+            key("marginal_high"),
+            marginal.max.to_string(),
+        ));
+    }
+
+    properties
+}
+
+fn outcome_label(outcome: MeasurementOutcome) -> &'static str {
+    match outcome {
+        MeasurementOutcome::Pass => "PASS",
+        MeasurementOutcome::Marginal => "MARGINAL",
+        MeasurementOutcome::Fail => "FAIL",
+        MeasurementOutcome::Unset => "UNSET",
+    }
+}
+
+/// Writes every measurement as a CSV row. Dimensioned measurements get one
+/// row per axis of every point, with `point` counting from 0, so sweeps can be
+/// pivoted back into columns. `raw_value` is only filled in for transformed
+/// measurements, the statistics columns only for sampled ones.
+pub struct CsvSink {
+    path: OutputPath,
+}
+
+impl CsvSink {
+    pub fn new(path: impl Into<OutputPath>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ReportSink for CsvSink {
+    fn write(&mut self, record: &RunRecord) -> Result<()> {
+        use std::io::Write;
+
+        let file = std::fs::File::create(self.path.create(record)?)?;
+        let mut out = std::io::BufWriter::new(file);
+
+        writeln!(
+            out,
+            "dut_id,suite,test,measurement,point,axis,value,raw_value,unit,low,high,mean,min,max,stddev,outcome"
+        )?;
+
+        for suite in &record.suites {
+            for test in &suite.tests {
+                for measurement in &test.measurements {
+                    let (low, high) = match &measurement.range {
+                        Some(range) => (range.min.to_string(), range.max.to_string()),
+                        None => Default::default(),
+                    };
+                    let statistics = match &measurement.statistics {
+                        Some(s) => [s.mean, s.min, s.max, s.stddev].map(|v| v.to_string()),
+                        None => Default::default(),
+                    };
+                    let mut row = |point: String,
+                                   axis: &str,
+                                   value: String,
+                                   raw_value: String,
+                                   unit: &str| {
+                        let fields = [
+                            record.dut_id.as_str(),
+                            &suite.name,
+                            &test.name,
+                            &measurement.name,
+                            &point,
+                            axis,
+                            &value,
+                            &raw_value,
+                            unit,
+                            &low,
+                            &high,
+                            &statistics[0],
+                            &statistics[1],
+                            &statistics[2],
+                            &statistics[3],
+                            outcome_label(measurement.outcome),
+                        ];
+                        let fields: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                        writeln!(out, "{}", fields.join(","))
+                    };
+
+                    match &measurement.value {
+                        Some(MeasurementValue::Dimensioned { axes, rows }) => {
+                            let raw_rows = match &measurement.raw_value {
+                                Some(MeasurementValue::Dimensioned { rows, .. }) => Some(rows),
+                                _ => None,
+                            };
+                            for (point, values) in rows.iter().enumerate() {
+                                for (i, (axis, value)) in axes.iter().zip(values).enumerate() {
+                                    let raw_value = raw_rows
+                                        .and_then(|rows| rows.get(point)?.get(i))
+                                        .map(|v| v.to_string())
+                                        .unwrap_or_default();
+                                    row(
+                                        point.to_string(),
+                                        &axis.name,
+                                        value.to_string(),
+                                        raw_value,
+                                        axis.unit.as_deref().unwrap_or_default(),
+                                    )?;
+                                }
+                            }
+                        }
+                        value => row(
+                            String::new(),
+                            "",
+                            value.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+                            measurement
+                                .raw_value
+                                .as_ref()
+                                .map(|v| v.to_string())
+                                .unwrap_or_default(),
+                            measurement.unit.as_deref().unwrap_or_default(),
+                        )?,
+                    }
+                }
+            }
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Writes the full run record as JSON, readable with [`RunRecord::load`]
+pub struct JsonSink {
+    path: OutputPath,
+}
+
+impl JsonSink {
+    pub fn new(path: impl Into<OutputPath>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl ReportSink for JsonSink {
+    fn write(&mut self, record: &RunRecord) -> Result<()> {
+        record.save(self.path.create(record)?)
+    }
+}
+
+pub fn default_sinks() -> Vec<Box<dyn ReportSink>> {
+    vec![
+        Box::new(JunitSink::new("junit-report.xml")),
+        Box::new(JsonSink::new("run-record.json")),
+    ]
+}
+
+/// Hands the record to every sink, a failing sink doesn't stop the others
+/// from running.
+pub fn write_reports(sinks: &mut [Box<dyn ReportSink>], record: &RunRecord) -> Result<()> {
+    let mut result = Ok(());
+
+    for sink in sinks {
+        if let Err(e) = sink.write(record) {
+            error!("Report sink failed: {:?}", e);
+            if result.is_ok() {
+                result = Err(e);
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("Rail"), "Rail");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("Rail, 3V3"), "\"Rail, 3V3\"");
+        assert_eq!(csv_field("5\" panel"), "\"5\"\" panel\"");
+        assert_eq!(csv_field("line 1\nline 2"), "\"line 1\nline 2\"");
+        assert_eq!(csv_field("line 1\r\n"), "\"line 1\r\n\"");
+    }
+}
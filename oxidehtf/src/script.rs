@@ -0,0 +1,105 @@
+//! Scripted answers to operator prompts, for running unattended.
+//!
+//! A response script answers prompts by id or by text, falling back to an
+//! ordered list of answers. It's read as JSON if the path ends in `.json` and
+//! as TOML otherwise:
+//!
+//! ```toml
+//! sequence = ["yes", "3.3"]
+//!
+//! [answers]
+//! dut-serial = "SN-0001"
+//! "Is the fixture closed?" = "yes"
+//! ```
+//!
+//! A prompt the script has no answer for fails the test it was asked in.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+};
+
+use serde::Deserialize;
+
+use crate::{
+    common::*,
+    test_runner::context::user_text_input::prompt::{Answer, AnswerSender, Prompt},
+    TestFailure,
+};
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ScriptFile {
+    #[serde(default)]
+    answers: HashMap<String, String>,
+    #[serde(default)]
+    sequence: VecDeque<String>,
+}
+
+/// Answers to operator prompts read from a TOML or JSON file
+#[derive(Debug, Clone)]
+pub struct ResponseScript {
+    answers: HashMap<String, String>,
+    sequence: VecDeque<String>,
+}
+
+impl ResponseScript {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| eyre!("Failed to read response script {}: {}", path.display(), e))?;
+
+        let file: ScriptFile = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => toml::from_str(&contents)?,
+        };
+
+        Ok(Self {
+            answers: file.answers,
+            sequence: file.sequence,
+        })
+    }
+
+    /// The answer to `prompt`, looked up by its id, then its text, then taken
+    /// from the front of the sequence
+    pub(crate) fn answer(&mut self, prompt: &Prompt) -> Result<String, TestFailure> {
+        let failed = |reason: String| TestFailure::ScriptedAnswerFailed {
+            prompt: prompt.text.clone(),
+            reason,
+        };
+
+        let answer = prompt
+            .id
+            .as_ref()
+            .and_then(|id| self.answers.get(id))
+            .or_else(|| self.answers.get(&prompt.text))
+            .cloned()
+            .or_else(|| self.sequence.pop_front())
+            .ok_or_else(|| failed("the script has no answer for it".into()))?;
+
+        prompt
+            .check(&answer)
+            .map_err(|reason| failed(format!("'{}' is not valid: {}", answer, reason)))
+    }
+
+    /// Answers `prompt` in place of the operator, returning what was sent
+    pub(crate) fn reply(
+        &mut self,
+        prompt: &Prompt,
+        reply: Option<AnswerSender>,
+    ) -> Result<String, TestFailure> {
+        let answer = self.answer(prompt);
+        match &answer {
+            Ok(text) => info!("Prompt: '{}' - Scripted Answer: '{}'", prompt, text),
+            Err(e) => error!("{}", e),
+        }
+
+        if let Some(reply) = reply {
+            let _ = reply.send(answer.clone().map(|text| Answer {
+                text,
+                scripted: true,
+            }));
+        }
+        answer
+    }
+}
@@ -1,32 +1,67 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use crate::common::*;
+use futures::future::BoxFuture;
+
+use crate::{
+    common::*,
+    limits::{LimitPrecedence, LimitsFile},
+};
 
 pub mod context;
 pub mod data;
 pub mod errors;
 pub mod executer;
 pub mod lifecycle;
+pub mod panic;
 
 pub use context::measurement::MeasurementDefinition;
 pub use context::SysContext;
 pub use data::suite::SuiteData;
-use data::suite::SuiteDataCollection;
 pub use data::suite::SuiteDataCollectionRaw;
+use data::suite::{SuiteDataCollection, TestHandle};
 pub use data::{TestDone, TestRunning, TestState};
-pub use errors::TestFailure;
+pub use errors::{RuleFailure, TestFailure};
+pub use executer::FailurePolicy;
 pub use executer::SuiteProducer;
 pub use executer::SuiteProducerGenerator;
-pub use lifecycle::TestLifecycle;
+pub use executer::TestFn;
+pub use lifecycle::{LifecycleHook, TestLifecycle};
 
 inventory::collect!(SuiteProducerGenerator);
 
+pub fn collect_suites() -> (Vec<SuiteData>, Vec<Box<dyn SuiteProducer>>) {
+    let mut builders = inventory::iter::<SuiteProducerGenerator>
+        .into_iter()
+        .collect::<Vec<&SuiteProducerGenerator>>();
+
+    builders.sort_by(|a, b| a.prio.cmp(&b.prio));
+
+    builders
+        .iter()
+        .map(|p| {
+            let executor = (p.func)();
+            let tests = executor
+                .get_tests()
+                .iter()
+                .map(|(name, _, config)| (*name, config.measurements))
+                .collect();
+            (
+                SuiteData::new(tests, executor.get_suite_name(), p.prio),
+                executor,
+            )
+        })
+        .collect()
+}
+
 pub struct TestRunner {
     executor: Vec<Box<dyn SuiteProducer>>,
     data: SuiteDataCollection,
     event_tx: UnboundedSender<Event>,
     context: SysContext,
     from_app_rx: UnboundedReceiver<Action>,
+    failure_policy: FailurePolicy,
+    limits: Option<LimitsFile>,
+    limit_precedence: LimitPrecedence,
 }
 
 impl TestRunner {
@@ -35,6 +70,9 @@ impl TestRunner {
         data: SuiteDataCollection,
         event_tx: UnboundedSender<Event>,
         from_app_rx: UnboundedReceiver<Action>,
+        failure_policy: FailurePolicy,
+        limits: Option<LimitsFile>,
+        limit_precedence: LimitPrecedence,
     ) -> Self {
         Self {
             executor,
@@ -42,68 +80,370 @@ impl TestRunner {
             event_tx: event_tx.clone(),
             context: SysContext::new(data.clone(), event_tx),
             from_app_rx,
+            failure_policy,
+            limits,
+            limit_precedence,
         }
     }
 
-    pub fn run(&mut self) -> Result<()> {
+    pub async fn run(&mut self) -> Result<()> {
         info!("Starting Test Runner");
 
+        panic::install_panic_hook();
+
         loop {
-            let action = self.from_app_rx.blocking_recv().unwrap();
-            match action {
-                Action::StartTests => break,
-                _ => (),
+            let action = self.from_app_rx.recv().await.unwrap();
+            if let Action::StartTests = action {
+                break;
             }
         }
 
-        for (suite_index, executor) in self.executor.iter_mut().enumerate() {
-            info!(
-                "Starting Suite: {}",
-                self.data.data.blocking_read().inner[suite_index].name
-            );
+        self.data.write(|f| f.set_run_start_time())?;
 
-            self.data.data.blocking_write().current = suite_index;
+        let executors = std::mem::take(&mut self.executor);
+        let mut executors = executors.into_iter().enumerate();
+
+        while let Some((suite_index, executor)) = executors.next() {
+            let suite_name = executor.get_suite_name();
+            let stop_run = self.run_suite(suite_index, executor).await?;
+            info!("Done");
 
-            self.data.blocking_write(|f| f.set_suite_start_time())?;
+            if stop_run {
+                warn!("Stopping run after failure in suite '{}'", suite_name);
+                let reason = format!("run stopped after suite '{}' failed", suite_name);
+                for (suite_index, executor) in executors.by_ref() {
+                    self.data.write(|f| {
+                        f.current = suite_index;
+                        Ok(())
+                    })?;
+                    self.finish_tests(
+                        0..executor.get_tests().len(),
+                        TestDone::Skipped(reason.clone()),
+                    )?;
+                }
+            }
+        }
 
-            executor.setup()?;
+        self.event_tx.send(Event::TestsCompleted)?;
+        Ok(())
+    }
 
-            for (test_index, (name, test)) in executor.get_tests().iter_mut().enumerate() {
-                self.data.blocking_write(|f| {
-                    f.current_suite_mut().update_test_index(test_index);
-                    f.current_suite_mut().current_test_mut().state =
-                        TestState::Running(TestRunning::Running);
-                    Ok(())
-                })?;
+    /// Runs every test in the suite, returning `true` if the failure policy
+    /// says the rest of the run should be stopped.
+    async fn run_suite(
+        &mut self,
+        suite_index: usize,
+        mut executor: Box<dyn SuiteProducer>,
+    ) -> Result<bool> {
+        self.data.write(|f| {
+            f.current = suite_index;
+            info!("Starting Suite: {}", f.current_suite().name);
+            f.set_suite_start_time()
+        })?;
 
-                info!("Starting Test: {}", name);
+        let suite_config = executor.get_suite_config();
+        let policy = suite_config.on_failure.unwrap_or(self.failure_policy);
+        let tests = executor.get_tests();
+        let test_count = tests.len();
 
-                executor.before_test()?;
-                let start_time = Instant::now();
-                let result = test(executor.as_mut(), &mut self.context);
-                let test_duration = Instant::now() - start_time;
-                executor.after_test()?;
+        if let Err(failure) = Self::run_hook(LifecycleHook::Setup, executor.setup_async()).await {
+            warn!("Suite setup failed: {}", failure);
+            self.data.write(|f| {
+                f.current_suite_mut().lifecycle_errors.push(failure.clone());
+                Ok(())
+            })?;
+            self.finish_tests(0..test_count, TestDone::Errored(failure))?;
+            self.teardown(executor).await?;
+            return Ok(policy == FailurePolicy::StopRun);
+        }
 
-                let final_state = match result {
-                    Ok(_) => TestState::Done(TestDone::Passed),
-                    Err(e) => TestState::Done(TestDone::Failed(e)),
-                };
+        let mut tests = tests.into_iter().enumerate();
 
-                self.data.blocking_write(|f| {
-                    f.current_suite_mut().current_test_mut().state = final_state;
-                    f.current_suite_mut().current_test_mut().duration = test_duration;
-                    Ok(())
-                })?;
+        while let Some((test_index, (name, test, config))) = tests.next() {
+            if let Some(reason) = config.skip {
+                info!("Skipping Test: {} - {}", name, reason);
+                self.finish_tests([test_index], TestDone::Skipped(reason.to_string()))?;
+                continue;
             }
 
-            executor.teardown()?;
+            self.data.write(|f| {
+                f.current_suite_mut().update_test_index(test_index);
+                f.current_suite_mut().current_test_mut().state =
+                    TestState::Running(TestRunning::Running);
+                Ok(())
+            })?;
 
-            info!("Done");
+            info!("Starting Test: {}", name);
+
+            let mut test_duration = Duration::default();
+
+            let before_test =
+                Self::run_hook(LifecycleHook::BeforeTest, executor.before_test_async()).await;
+
+            let outcome = match before_test {
+                Err(failure) => TestDone::Errored(failure),
+                Ok(()) => {
+                    let start_time = Instant::now();
+                    let limit = config.timeout.or(suite_config.timeout);
+                    self.context
+                        .measurements
+                        .set_non_fatal(config.non_fatal_measurements);
+                    let limits = self
+                        .limits
+                        .as_ref()
+                        .map(|l| l.test_specs(executor.get_suite_name(), name))
+                        .unwrap_or_default();
+                    self.context
+                        .measurements
+                        .set_limits(limits, self.limit_precedence);
+                    let handle = TestHandle::new(self.data.clone(), suite_index, test_index);
+                    self.context.start_test(handle.clone());
+                    let (returned, result) = self.run_test(executor, test, limit, &handle).await;
+                    test_duration = Instant::now() - start_time;
+                    let skip_reason = self.context.take_skip_reason();
+
+                    let mut declared: Vec<String> =
+                        config.measurements.iter().map(|m| m.to_string()).collect();
+                    declared.extend(self.context.measurements.take_declared());
+
+                    let (marginal, measurement_failure) = self.data.write(|d| {
+                        let test = d.current_suite_mut().current_test_mut();
+                        let unset = test.check_declared(&declared);
+                        Ok((
+                            test.has_marginal_measurement(),
+                            test.measurement_failure().or(unset),
+                        ))
+                    })?;
+
+                    // Non fatal measurement failures and unset declared measurements
+                    // only show up once the test returns
+                    let outcome = match (result, skip_reason, measurement_failure) {
+                        (Ok(_), Some(reason), _) => TestDone::Skipped(reason),
+                        (Ok(_), None, Some(failure)) => TestDone::Failed(failure),
+                        (Ok(_), None, None) if marginal => TestDone::PassedMarginal,
+                        (Ok(_), None, None) => TestDone::Passed,
+                        (Err(e), _, _) => TestDone::Failed(e),
+                    };
+
+                    let returned_executor = match returned {
+                        Returned::Suite(returned_executor) => returned_executor,
+                        // The suite is still owned by the thread of the test that
+                        // overran, so nothing else in it can run
+                        returned => {
+                            self.finish_test(outcome, test_duration)?;
+                            let reason = format!("test '{}' did not finish", name);
+                            self.finish_tests(
+                                tests.map(|(index, _)| index),
+                                TestDone::Skipped(reason),
+                            )?;
+                            self.finish_overrun(name, returned, suite_config.abandon_on_timeout)
+                                .await?;
+                            return Ok(policy == FailurePolicy::StopRun);
+                        }
+                    };
+
+                    executor = returned_executor;
+                    outcome
+                }
+            };
+
+            let after_test =
+                Self::run_hook(LifecycleHook::AfterTest, executor.after_test_async()).await;
+
+            let outcome = match (outcome, after_test) {
+                (TestDone::Passed | TestDone::PassedMarginal, Err(failure)) => {
+                    TestDone::Errored(failure)
+                }
+                (outcome, Err(failure)) => {
+                    warn!("Test '{}' already failed, ignoring: {}", name, failure);
+                    outcome
+                }
+                (outcome, Ok(())) => outcome,
+            };
+
+            let failed = outcome.is_failure();
+            self.finish_test(outcome, test_duration)?;
+
+            if failed && policy != FailurePolicy::Continue {
+                warn!("Stopping suite after test '{}' failed", name);
+                let reason = format!("suite stopped after test '{}' failed", name);
+                self.finish_tests(tests.map(|(index, _)| index), TestDone::Skipped(reason))?;
+                self.teardown(executor).await?;
+                return Ok(policy == FailurePolicy::StopRun);
+            }
         }
 
-        self.event_tx.send(Event::TestsCompleted)?;
-        Ok(())
+        let teardown_failed = self.teardown(executor).await?;
+        Ok(teardown_failed && policy == FailurePolicy::StopRun)
     }
+
+    /// Tears down the suite of a test that overran once the test returns, so
+    /// the fixture isn't released under a test that's still using it and the
+    /// next suite doesn't start until it is. Records the missed teardown if
+    /// the suite is abandoned instead.
+    async fn finish_overrun(
+        &mut self,
+        test: &str,
+        returned: Returned,
+        abandon: bool,
+    ) -> Result<()> {
+        match returned {
+            Returned::Overran(thread) if !abandon => {
+                warn!(
+                    "Waiting for test '{}' to return to tear its suite down",
+                    test
+                );
+                match thread.await {
+                    Ok((executor, context, _)) => {
+                        self.context = context;
+                        self.teardown(executor).await?;
+                        return Ok(());
+                    }
+                    Err(e) => error!("Failed to join test: {:?}", e),
+                }
+            }
+            _ => warn!("Abandoning suite after test '{}' did not finish", test),
+        }
+
+        self.data.write(|f| {
+            f.current_suite_mut()
+                .lifecycle_errors
+                .push(TestFailure::LifecycleFailed {
+                    hook: LifecycleHook::Teardown,
+                    message: format!("not run, test '{}' did not finish", test),
+                });
+            Ok(())
+        })
+    }
+
+    /// Returns `true` if the teardown hook failed
+    async fn teardown(&mut self, mut executor: Box<dyn SuiteProducer>) -> Result<bool> {
+        let Err(failure) = Self::run_hook(LifecycleHook::Teardown, executor.teardown_async()).await
+        else {
+            return Ok(false);
+        };
+
+        warn!("Suite teardown failed: {}", failure);
+        self.data.write(|f| {
+            f.current_suite_mut().lifecycle_errors.push(failure);
+            Ok(())
+        })?;
+
+        Ok(true)
+    }
+
+    async fn run_hook(
+        hook: LifecycleHook,
+        future: BoxFuture<'_, Result<()>>,
+    ) -> Result<(), TestFailure> {
+        let message = match panic::catch_panic_async(future).await {
+            Ok(Ok(())) => return Ok(()),
+            Ok(Err(e)) => format!("{:#}", e),
+            Err(panicked) => panicked.to_string(),
+        };
+
+        Err(TestFailure::LifecycleFailed { hook, message })
+    }
+
+    fn finish_test(&self, outcome: TestDone, duration: Duration) -> Result<()> {
+        self.data.write(|f| {
+            f.current_suite_mut().current_test_mut().state = TestState::Done(outcome);
+            f.current_suite_mut().current_test_mut().duration = duration;
+            Ok(())
+        })
+    }
+
+    fn finish_tests(
+        &self,
+        indices: impl IntoIterator<Item = usize>,
+        outcome: TestDone,
+    ) -> Result<()> {
+        self.data.write(|f| {
+            for index in indices {
+                f.current_suite_mut().update_test_index(index);
+                f.current_suite_mut().current_test_mut().state = TestState::Done(outcome.clone());
+            }
+            Ok(())
+        })
+    }
+
+    /// Runs a single test, handing back the suite unless a blocking test
+    /// exceeded `limit`, in which case `handle` is cancelled and the test's
+    /// thread is handed back instead. Panics are returned as
+    /// [`TestFailure::Panicked`].
+    async fn run_test(
+        &mut self,
+        mut executor: Box<dyn SuiteProducer>,
+        test: TestFn,
+        limit: Option<Duration>,
+        handle: &TestHandle,
+    ) -> (Returned, Result<(), TestFailure>) {
+        let start_time = Instant::now();
+        let timeout_failure = |limit| TestFailure::Timeout {
+            limit,
+            elapsed: Instant::now() - start_time,
+        };
+
+        match test {
+            TestFn::Sync(test) => {
+                let mut context = SysContext::new(self.data.clone(), self.event_tx.clone());
+                std::mem::swap(&mut context, &mut self.context);
+
+                let mut thread = tokio::task::spawn_blocking(move || {
+                    let result = panic::catch_panic(|| test(executor.as_mut(), &mut context));
+                    (executor, context, result.and_then(|r| r))
+                });
+
+                let joined = match limit {
+                    Some(limit) => match tokio::time::timeout(limit, &mut thread).await {
+                        Ok(joined) => joined,
+                        Err(_) => {
+                            handle.cancel();
+                            return (Returned::Overran(thread), Err(timeout_failure(limit)));
+                        }
+                    },
+                    None => thread.await,
+                };
+
+                match joined {
+                    Ok((executor, context, result)) => {
+                        self.context = context;
+                        (Returned::Suite(executor), result)
+                    }
+                    Err(e) => {
+                        error!("Failed to join test: {:?}", e);
+                        (Returned::Lost, Err(TestFailure::SystemError))
+                    }
+                }
+            }
+            TestFn::Async(test) => {
+                let result = {
+                    let future =
+                        panic::catch_panic_async(test(executor.as_mut(), &mut self.context));
+                    let result = match limit {
+                        Some(limit) => tokio::time::timeout(limit, future)
+                            .await
+                            .unwrap_or_else(|_| Ok(Err(timeout_failure(limit)))),
+                        None => future.await,
+                    };
+                    result.and_then(|r| r)
+                };
+                (Returned::Suite(executor), result)
+            }
+        }
+    }
+}
+
+type TestThread =
+    tokio::task::JoinHandle<(Box<dyn SuiteProducer>, SysContext, Result<(), TestFailure>)>;
+
+/// What a test hands back once [`TestRunner::run_test`] stops waiting for it
+enum Returned {
+    Suite(Box<dyn SuiteProducer>),
+    /// A blocking test overran its limit, its thread still owns the suite
+    Overran(TestThread),
+    /// The test's thread failed, the suite is gone
+    Lost,
 }
 
 impl std::fmt::Display for TestState {
@@ -120,7 +460,12 @@ impl std::fmt::Display for TestDone {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Passed => write!(f, "Passed"),
+            Self::PassedMarginal => write!(f, "Marginal Pass"),
+            Self::Failed(TestFailure::Timeout { .. }) => write!(f, "Timed Out"),
+            Self::Failed(TestFailure::Panicked { .. }) => write!(f, "Panicked"),
             Self::Failed(_) => write!(f, "Failed"),
+            Self::Errored(_) => write!(f, "Errored"),
+            Self::Skipped(_) => write!(f, "Skipped"),
         }
     }
 }
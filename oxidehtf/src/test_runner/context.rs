@@ -2,26 +2,54 @@ use dut::Dut;
 use measurement::Measurements;
 use user_text_input::TextInput;
 
-use crate::common::*;
+use crate::{common::*, TestFailure};
 
-use super::data::suite::SuiteDataCollection;
+use super::data::suite::{SuiteDataCollection, TestHandle};
 
 pub mod dut;
 pub mod measurement;
 pub mod user_text_input;
 
+/// Waits for `future` from a sync test, which runs on a blocking thread of the
+/// runtime. Blocking prompts are built on their `_async` versions with this.
+pub(crate) fn wait<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Handle::current().block_on(future)
+}
+
 pub struct SysContext {
     pub text_input: TextInput,
     pub measurements: Measurements,
     pub dut: Dut,
+    skip_reason: Option<String>,
 }
 
 impl SysContext {
     pub fn new(suite_data: SuiteDataCollection, event_tx: UnboundedSender<Event>) -> Self {
         Self {
-            text_input: TextInput::new(event_tx.clone(), suite_data.clone()),
-            measurements: Measurements::new(suite_data.clone()),
+            text_input: TextInput::new(event_tx.clone(), TestHandle::new(suite_data.clone(), 0, 0)),
+            measurements: Measurements::new(TestHandle::new(suite_data, 0, 0)),
             dut: Dut::new(event_tx.clone()),
+            skip_reason: None,
         }
     }
+
+    /// Points the context at the test about to run, set by the runner
+    pub(crate) fn start_test(&mut self, test: TestHandle) {
+        self.text_input.set_test(test.clone());
+        self.measurements.set_test(test);
+        self.skip_reason = None;
+    }
+
+    /// Marks the current test as skipped rather than passed, for use as
+    /// `return context.skip("DUT variant has no radio");`. A failure the test
+    /// returns after skipping still fails it.
+    pub fn skip(&mut self, reason: impl Into<String>) -> Result<(), TestFailure> {
+        self.skip_reason = Some(reason.into());
+        Ok(())
+    }
+
+    /// Why the test that just ran skipped itself, if it did
+    pub(crate) fn take_skip_reason(&mut self) -> Option<String> {
+        self.skip_reason.take()
+    }
 }
@@ -18,7 +18,14 @@ impl Dut {
     }
 
     pub fn set_via_operator(&self, text_input: &mut TextInput) -> Result<(), TestFailure> {
-        let input = text_input.request("Enter DUT:")?;
+        super::wait(self.set_via_operator_async(text_input))
+    }
+
+    pub async fn set_via_operator_async(
+        &self,
+        text_input: &mut TextInput,
+    ) -> Result<(), TestFailure> {
+        let input = text_input.request_async("Enter DUT:").await?;
         self.set_id(input)
     }
 }
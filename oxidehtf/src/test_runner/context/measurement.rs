@@ -1,131 +1,830 @@
 use std::collections::HashMap;
 use std::panic::Location;
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::test_runner::{data::suite::SuiteDataCollection, TestFailure};
+use cli_log::warn;
 
-#[derive(Debug, PartialEq, Clone)]
-pub enum Unit {
-    Volts,
-}
+use crate::{
+    limits::{LimitPrecedence, LimitSpec},
+    test_runner::{data::suite::TestHandle, RuleFailure, TestFailure},
+};
+
+pub mod transforms;
+pub mod unit;
+pub mod validators;
 
-#[derive(Debug, Clone)]
+use transforms::{ConvertUnit, Map, Mean, Offset, Scale, Transform};
+use unit::{Limit, MeasurementUnit};
+use validators::{
+    AtLeast, AtMost, Custom, EachPoint, Equals, InRange, Matches, OneOf, Validator, WithinPercent,
+};
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum DataTypes {
     F64(f64),
+    I64(i64),
+    U64(u64),
+    Bool(bool),
+    /// A value that must be one of `allowed`
+    Enum {
+        value: String,
+        allowed: Vec<String>,
+    },
+    Duration(Duration),
+    Bytes(Vec<u8>),
     String(String),
+    /// Rows of `(x, y...)` points, e.g. a sweep or a waveform. Every row has
+    /// one value per axis.
+    Dimensioned {
+        axes: Vec<Axis>,
+        rows: Vec<Vec<f64>>,
+    },
+    /// Repeated readings of the same quantity
+    Samples(Vec<f64>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    pub name: String,
+    pub unit: Option<MeasurementUnit>,
+}
+
+impl Axis {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            unit: None,
+        }
+    }
+
+    pub fn with_unit(mut self, unit: impl Into<MeasurementUnit>) -> Self {
+        self.unit = Some(unit.into());
+        self
+    }
+}
+
+impl From<&str> for Axis {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<(&str, &str)> for Axis {
+    fn from((name, unit): (&str, &str)) -> Self {
+        Self::new(name).with_unit(unit)
+    }
+}
+
+impl std::fmt::Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} ({})", self.name, unit),
+            None => write!(f, "{}", self.name),
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+impl DataTypes {
+    /// The value used for range checks, durations are in seconds
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::F64(v) => Some(*v),
+            Self::I64(v) => Some(*v as f64),
+            Self::U64(v) => Some(*v as f64),
+            Self::Duration(v) => Some(v.as_secs_f64()),
+            _ => None,
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::F64(_) => "f64",
+            Self::I64(_) => "i64",
+            Self::U64(_) => "u64",
+            Self::Bool(_) => "bool",
+            Self::Enum { .. } => "enum",
+            Self::Duration(_) => "duration",
+            Self::Bytes(_) => "bytes",
+            Self::String(_) => "string",
+            Self::Dimensioned { .. } => "dimensioned",
+            Self::Samples(_) => "samples",
+        }
+    }
+
+    /// Statistics of a vector of samples, `None` for other values
+    pub fn statistics(&self) -> Option<Statistics> {
+        match self {
+            Self::Samples(samples) => Statistics::of(samples),
+            _ => None,
+        }
+    }
+}
+
+/// Summary of repeated samples of a measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub count: usize,
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    /// Sample standard deviation, 0 for a single sample
+    pub stddev: f64,
+}
+
+impl Statistics {
+    /// `None` if there are no samples
+    pub fn of(samples: &[f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let count = samples.len();
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let variance = match count {
+            1 => 0.0,
+            _ => samples.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1) as f64,
+        };
+
+        Some(Self {
+            count,
+            mean,
+            min: samples.iter().copied().fold(f64::INFINITY, f64::min),
+            max: samples.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            stddev: variance.sqrt(),
+        })
+    }
+}
+
+impl std::fmt::Display for Statistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mean {:.4} | min {:.4} | max {:.4} | stddev {:.4}",
+            self.mean, self.min, self.max, self.stddev
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct MeasurementDefinition {
-    pub unit: Option<String>,
-    pub range: Option<(f64, f64)>,
+    pub unit: Option<MeasurementUnit>,
+    pub validators: Vec<Arc<dyn Validator>>,
+    /// Warning limits inside the spec limits, a value outside of them still
+    /// passes but is marked marginal
+    pub marginal: Option<(f64, f64)>,
+    /// A failure is recorded without ending the test, see
+    /// [`MeasurementSetter::non_fatal`]
+    pub non_fatal: bool,
+    /// Applied in order to the value before it's validated
+    pub transforms: Vec<Arc<dyn Transform>>,
+    /// The value as it was set, before any transforms
+    pub raw_value: Option<DataTypes>,
+    /// The value that was validated
     pub value: Option<DataTypes>,
+    pub status: MeasurementStatus,
+    pub failure: Option<TestFailure>,
+}
+
+impl MeasurementDefinition {
+    /// The first numeric limits given by any of the validators
+    pub fn limits(&self) -> Option<(f64, f64)> {
+        self.validators.iter().find_map(|v| v.limits())
+    }
+
+    /// Samples and dimensioned values are marginal if any sample or `y`
+    /// value is outside of the marginal limits
+    fn is_marginal(&self) -> bool {
+        let (Some((min, max)), Some(value)) = (self.marginal, &self.value) else {
+            return false;
+        };
+        let outside = |v: f64| v < min || v > max;
+
+        match value {
+            DataTypes::Samples(samples) => samples.iter().copied().any(outside),
+            DataTypes::Dimensioned { rows, .. } => rows
+                .iter()
+                .flat_map(|row| row.iter().skip(1))
+                .copied()
+                .any(outside),
+            value => value.as_f64().is_some_and(outside),
+        }
+    }
+
+    /// Statistics of the samples, taken before any transform reduced them
+    pub fn statistics(&self) -> Option<Statistics> {
+        self.raw_value
+            .as_ref()
+            .and_then(DataTypes::statistics)
+            .or_else(|| self.value.as_ref()?.statistics())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementStatus {
+    #[default]
+    Unset,
+    Pass,
+    /// Passed, but outside of the marginal limits
+    Marginal,
+    Fail,
 }
 
+/// The measurements of the running test. Setting a value only takes a short
+/// lock on the run's data, so the same setters are used from sync and async
+/// tests.
 pub struct Measurements {
     definitions: HashMap<String, MeasurementDefinition>,
-    suites_data: SuiteDataCollection,
+    test: TestHandle,
+    non_fatal: bool,
+    declared: Vec<String>,
+    limits: HashMap<String, LimitSpec>,
+    limit_precedence: LimitPrecedence,
 }
 
 impl Measurements {
-    pub fn new(suites_data: SuiteDataCollection) -> Self {
+    pub fn new(test: TestHandle) -> Self {
         Measurements {
             definitions: HashMap::new(),
-            suites_data,
+            test,
+            non_fatal: false,
+            declared: Vec::new(),
+            limits: HashMap::new(),
+            limit_precedence: LimitPrecedence::default(),
         }
     }
 
+    /// Points the measurements at the next test, set by the runner
+    pub(crate) fn set_test(&mut self, test: TestHandle) {
+        self.test = test;
+    }
+
+    /// Limits from the limits file for the measurements of the next test, set
+    /// by the runner
+    pub(crate) fn set_limits(
+        &mut self,
+        limits: HashMap<String, LimitSpec>,
+        precedence: LimitPrecedence,
+    ) {
+        self.limits = limits;
+        self.limit_precedence = precedence;
+    }
+
+    /// Makes every measurement of the next test non fatal, set by the runner
+    /// from [`crate::TestConfig::non_fatal_measurements`]
+    pub(crate) fn set_non_fatal(&mut self, non_fatal: bool) {
+        self.non_fatal = non_fatal;
+    }
+
+    /// Declares a measurement the current test must set, the test fails if
+    /// it's still unset once the test returns. Declarations made with
+    /// `#[test(measurements = [...])]` are also shown before the run starts.
+    pub fn declare(&mut self, name: impl Into<String>) {
+        self.declared.push(name.into());
+    }
+
+    pub(crate) fn take_declared(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.declared)
+    }
+
     pub fn measure(&mut self, name: impl Into<String>) -> MeasurementSetter<'_> {
         let name = name.into();
-        self.definitions
-            .entry(name.clone())
-            .or_insert_with(|| MeasurementDefinition {
-                unit: None,
-                range: None,
-                value: None,
-            });
+        self.definitions.entry(name.clone()).or_default();
 
         MeasurementSetter {
             manager: self,
             name,
             unit: None,
-            range: None,
+            validators: Vec::new(),
+            marginal: None,
+            non_fatal: false,
+            axes: Vec::new(),
+            transforms: Vec::new(),
         }
     }
 
-    #[track_caller]
-    fn set_value_internal(&mut self, name: &str, value: DataTypes) -> Result<(), TestFailure> {
+    fn take_definition(
+        &mut self,
+        name: &str,
+        value: DataTypes,
+        location: &'static Location<'static>,
+    ) -> Result<(MeasurementDefinition, Result<(), TestFailure>), TestFailure> {
         let Some(mut def) = self.definitions.remove(name) else {
             return Err(TestFailure::MeasurementDoesntExist(name.into()));
         };
 
-        def.value = Some(value.clone());
-
-        self.suites_data
-            .blocking_write(|f| Ok(f.current_suite_mut().insert_measurement(name, def.clone())))
-            .expect("Failed to write measuremnt");
-
-        if let DataTypes::F64(value) = value {
-            if let Some((min, max)) = def.range {
-                if value < min || value > max {
-                    return Err(TestFailure::MeasurementNotInRange {
-                        name: name.into(),
-                        expected: (min, max),
-                        found: value,
-                        file: Location::caller().file(),
-                        line: Location::caller().line(),
-                    });
+        let transformed = def.transforms.iter().try_fold(value.clone(), |value, t| {
+            t.apply(value)
+                .map_err(|reason| TestFailure::MeasurementFailed {
+                    name: name.into(),
+                    failures: vec![RuleFailure {
+                        rule: t.describe(),
+                        reason,
+                    }],
+                    file: location.file(),
+                    line: location.line(),
+                })
+        });
+        if !def.transforms.is_empty() {
+            def.raw_value = Some(value);
+        }
+        def.value = transformed.as_ref().ok().cloned();
+
+        let result = transformed.and_then(|_| Self::check_definition(name, &def, location));
+        def.status = match result {
+            Ok(_) if def.is_marginal() => MeasurementStatus::Marginal,
+            Ok(_) => MeasurementStatus::Pass,
+            Err(_) => MeasurementStatus::Fail,
+        };
+        def.failure = result.as_ref().err().cloned();
+
+        if def.non_fatal || self.non_fatal {
+            if let Err(failure) = &result {
+                warn!("Continuing after non fatal failure: {}", failure);
+            }
+            return Ok((def, Ok(())));
+        }
+
+        Ok((def, result))
+    }
+
+    fn check_definition(
+        name: &str,
+        def: &MeasurementDefinition,
+        location: &'static Location<'static>,
+    ) -> Result<(), TestFailure> {
+        let Some(value) = &def.value else {
+            return Ok(());
+        };
+
+        let failed = |failures| TestFailure::MeasurementFailed {
+            name: name.into(),
+            failures,
+            file: location.file(),
+            line: location.line(),
+        };
+
+        let mut failures = Vec::new();
+        match value {
+            DataTypes::Enum { value, allowed } if !allowed.contains(value) => {
+                failures.push(RuleFailure {
+                    rule: format!("one of [{}]", allowed.join(", ")),
+                    reason: format!("found {}", value),
+                });
+            }
+            DataTypes::Dimensioned { axes, rows } => {
+                // Points can't be checked against their axes
+                if let Some((index, row)) = rows
+                    .iter()
+                    .enumerate()
+                    .find(|(_, row)| row.len() != axes.len())
+                {
+                    return Err(failed(vec![RuleFailure {
+                        rule: format!("{} values per point", axes.len()),
+                        reason: format!("point {} has {}", index, row.len()),
+                    }]));
                 }
             }
+            _ => (),
         }
 
-        Ok(())
+        failures.extend(def.validators.iter().filter_map(|validator| {
+            let reason = validator.validate(value).err()?;
+            Some(RuleFailure {
+                rule: validator.describe(),
+                reason,
+            })
+        }));
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failed(failures))
+        }
     }
 
-    pub(crate) fn update_definition(
+    fn set_value_internal(
         &mut self,
         name: &str,
-        unit: Option<String>,
-        range: Option<(f64, f64)>,
-    ) {
-        if let Some(def) = self.definitions.get_mut(name) {
-            def.unit = unit.or(def.unit.clone());
-            def.range = range.or(def.range);
-        }
+        value: DataTypes,
+        location: &'static Location<'static>,
+    ) -> Result<(), TestFailure> {
+        let (def, result) = self.take_definition(name, value, location)?;
+
+        self.test.write(|test, _| {
+            test.user_data.insert(name.into(), def);
+        });
+
+        result
     }
 }
 
 pub struct MeasurementSetter<'a> {
     manager: &'a mut Measurements,
     name: String,
-    unit: Option<String>,
-    range: Option<(f64, f64)>,
+    unit: Option<MeasurementUnit>,
+    validators: Vec<PendingValidator>,
+    marginal: Option<(Limit, Limit)>,
+    non_fatal: bool,
+    axes: Vec<Axis>,
+    transforms: Vec<Arc<dyn Transform>>,
+}
+
+/// A validator whose limits may still need converting into the
+/// measurement's unit
+pub(crate) enum PendingValidator {
+    Ready(Arc<dyn Validator>),
+    InRange(Limit, Limit),
+    AtLeast(Limit),
+    AtMost(Limit),
+}
+
+impl PendingValidator {
+    fn resolve(self, unit: Option<&MeasurementUnit>) -> Arc<dyn Validator> {
+        let (rule, resolved) = match self {
+            Self::Ready(validator) => return validator,
+            Self::InRange(min, max) => (
+                format!("in range {} to {}", min, max),
+                min.resolve(unit).and_then(|min| {
+                    let max = max.resolve(unit)?;
+                    Ok(Arc::new(InRange { min, max }) as Arc<dyn Validator>)
+                }),
+            ),
+            Self::AtLeast(min) => (
+                format!("at least {}", min),
+                min.resolve(unit)
+                    .map(|min| Arc::new(AtLeast(min)) as Arc<dyn Validator>),
+            ),
+            Self::AtMost(max) => (
+                format!("at most {}", max),
+                max.resolve(unit)
+                    .map(|max| Arc::new(AtMost(max)) as Arc<dyn Validator>),
+            ),
+        };
+
+        resolved.unwrap_or_else(|reason| failing(rule, reason))
+    }
+
+    /// Whether the validator sets numeric limits, which limits from the
+    /// limits file replace
+    fn is_limit(&self) -> bool {
+        match self {
+            Self::Ready(validator) => validator.is_limit(),
+            _ => true,
+        }
+    }
+
+    fn from_spec(spec: &LimitSpec, unit: Option<&MeasurementUnit>) -> Option<Self> {
+        let limit = |value| spec_limit(value, spec, unit);
+        match (spec.min, spec.max) {
+            (Some(min), Some(max)) => Some(Self::InRange(limit(min), limit(max))),
+            (Some(min), None) => Some(Self::AtLeast(limit(min))),
+            (None, Some(max)) => Some(Self::AtMost(limit(max))),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Stands in for a rule whose limits can't be compared with the measurement,
+/// failing it with the reason
+fn failing(rule: String, reason: String) -> Arc<dyn Validator> {
+    Arc::new(Custom::new(rule, move |_: &DataTypes| Err(reason.clone())))
+}
+
+/// A limit from the limits file, in the spec's unit if it has one
+fn spec_limit(value: f64, spec: &LimitSpec, unit: Option<&MeasurementUnit>) -> Limit {
+    let Some(spec_unit) = spec.unit.as_deref().map(MeasurementUnit::from) else {
+        return Limit::Plain(value);
+    };
+
+    match spec_unit {
+        spec_unit if Some(&spec_unit) == unit => Limit::Plain(value),
+        MeasurementUnit::Scaled(spec_unit) => (value, spec_unit).into(),
+        MeasurementUnit::Other(spec_unit) => {
+            Limit::Invalid(format!("can't convert a limit in '{}'", spec_unit))
+        }
+    }
 }
 
 impl<'a> MeasurementSetter<'a> {
-    pub fn with_unit(mut self, unit: impl Into<String>) -> Self {
+    /// Accepts a [`unit::Unit`], a [`unit::ScaledUnit`] or a string. Strings
+    /// such as `"mV"` or `"Volts"` are parsed so limits given in other units
+    /// can be converted.
+    pub fn with_unit(mut self, unit: impl Into<MeasurementUnit>) -> Self {
         self.unit = Some(unit.into());
         self
     }
 
-    pub fn in_range(mut self, min: f64, max: f64) -> Self {
-        self.range = Some((min, max));
+    /// Adds a rule the value must pass, see [`validators`] for the built in
+    /// ones.
+    pub fn validate_with(mut self, validator: impl Validator + 'static) -> Self {
+        self.validators
+            .push(PendingValidator::Ready(Arc::new(validator)));
         self
     }
 
-    #[track_caller]
-    fn set_internal(self, value: DataTypes) -> Result<(), TestFailure> {
-        self.manager
-            .update_definition(&self.name, self.unit, self.range);
-        self.manager.set_value_internal(&self.name, value)
+    /// Checks any numeric value, durations are compared in seconds. Limits
+    /// with a unit, e.g. `in_range("3.0 V", "3.6 V")`, are converted to the
+    /// measurement's unit.
+    pub fn in_range(mut self, min: impl Into<Limit>, max: impl Into<Limit>) -> Self {
+        self.validators
+            .push(PendingValidator::InRange(min.into(), max.into()));
+        self
+    }
+
+    /// Warning limits, a numeric value that passes its validators but falls
+    /// outside of these makes the test a marginal pass. Samples and
+    /// dimensioned values are marginal if any sample or `y` value is outside.
+    /// Limits with a unit are converted like those of [`Self::in_range`].
+    pub fn marginal_range(mut self, min: impl Into<Limit>, max: impl Into<Limit>) -> Self {
+        self.marginal = Some((min.into(), max.into()));
+        self
+    }
+
+    /// Names the axes of a dimensioned measurement, the first is the swept
+    /// `x` axis, e.g. `.with_axes([("Frequency", "Hz"), ("Gain", "dB")])`
+    pub fn with_axes<A: Into<Axis>>(mut self, axes: impl IntoIterator<Item = A>) -> Self {
+        self.axes = axes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Checks every `y` value of a dimensioned measurement against
+    /// `validator`. Validators added with [`Self::validate_with`] see the
+    /// whole measurement instead, see [`validators::Aggregate`].
+    pub fn each_point(self, validator: impl Validator + 'static) -> Self {
+        self.validate_with(EachPoint::new(validator))
+    }
+
+    /// Adds a step that turns the set value into the one that's validated,
+    /// the value as set is kept as the raw value. See [`transforms`] for the
+    /// built in ones.
+    pub fn transform(mut self, transform: impl Transform + 'static) -> Self {
+        self.transforms.push(Arc::new(transform));
+        self
+    }
+
+    pub fn scale(self, factor: f64) -> Self {
+        self.transform(Scale(factor))
+    }
+
+    pub fn offset(self, offset: f64) -> Self {
+        self.transform(Offset(offset))
+    }
+
+    /// Converts a value measured in `from` to `to`, which becomes the
+    /// measurement's unit unless one was given with [`Self::with_unit`]
+    pub fn convert_unit(
+        mut self,
+        from: impl Into<MeasurementUnit>,
+        to: impl Into<MeasurementUnit>,
+    ) -> Self {
+        let to = to.into();
+        self.unit = self.unit.or(Some(to.clone()));
+        self.transform(ConvertUnit::new(from, to))
+    }
+
+    /// Averages the values set with [`Self::set_samples`]
+    pub fn mean(self) -> Self {
+        self.transform(Mean)
+    }
+
+    /// Applies `f` to the value, `description` names it in failures and
+    /// reports
+    pub fn map<F>(self, description: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(DataTypes) -> Result<DataTypes, String> + Send + Sync + 'static,
+    {
+        self.transform(Map::new(description, f))
+    }
+
+    /// Records a failing value without returning an error, so the test keeps
+    /// running and fails once it returns
+    pub fn non_fatal(mut self) -> Self {
+        self.non_fatal = true;
+        self
+    }
+
+    pub fn at_least(mut self, min: impl Into<Limit>) -> Self {
+        self.validators.push(PendingValidator::AtLeast(min.into()));
+        self
+    }
+
+    pub fn at_most(mut self, max: impl Into<Limit>) -> Self {
+        self.validators.push(PendingValidator::AtMost(max.into()));
+        self
+    }
+
+    pub fn equals(self, expected: impl Into<DataTypes>) -> Self {
+        self.validate_with(Equals(expected.into()))
+    }
+
+    pub fn within_percent(self, nominal: f64, percent: f64) -> Self {
+        self.validate_with(WithinPercent { nominal, percent })
+    }
+
+    /// Checks string values against a regular expression, an invalid
+    /// pattern fails the measurement
+    pub fn matches(self, pattern: impl Into<String>) -> Self {
+        self.validate_with(Matches::new(pattern))
+    }
+
+    pub fn one_of<T: Into<DataTypes>>(self, options: impl IntoIterator<Item = T>) -> Self {
+        self.validate_with(OneOf(options.into_iter().map(Into::into).collect()))
+    }
+
+    pub fn in_duration_range(self, min: Duration, max: Duration) -> Self {
+        self.in_range(min.as_secs_f64(), max.as_secs_f64())
+    }
+
+    /// Merges everything set on the setter into the measurement's definition
+    fn update_definition(self) -> (&'a mut Measurements, String) {
+        let spec = self.manager.limits.get(&self.name).cloned();
+        let precedence = self.manager.limit_precedence;
+
+        if let Some(def) = self.manager.definitions.get_mut(&self.name) {
+            def.non_fatal |= self.non_fatal;
+            def.unit = self
+                .unit
+                .or(def.unit.clone())
+                .or_else(|| spec.as_ref()?.unit.as_deref().map(Into::into));
+
+            let mut validators = self.validators;
+            let mut marginal = match self.marginal {
+                Some((min, max)) => {
+                    let range = min
+                        .resolve(def.unit.as_ref())
+                        .and_then(|min| Ok((min, max.resolve(def.unit.as_ref())?)));
+                    range
+                        .map_err(|reason| {
+                            let rule = format!("marginal range {} to {}", min, max);
+                            validators.push(PendingValidator::Ready(failing(rule, reason)));
+                        })
+                        .ok()
+                }
+                None => def.marginal,
+            };
+            if let Some(spec) = &spec {
+                let spec_marginal = spec.marginal.and_then(|[min, max]| {
+                    let min = spec_limit(min, spec, def.unit.as_ref());
+                    let max = spec_limit(max, spec, def.unit.as_ref());
+                    Some((
+                        min.resolve(def.unit.as_ref()).ok()?,
+                        max.resolve(def.unit.as_ref()).ok()?,
+                    ))
+                });
+                let from_spec = PendingValidator::from_spec(spec, def.unit.as_ref());
+
+                match precedence {
+                    LimitPrecedence::File => {
+                        validators.retain(|v| !v.is_limit());
+                        validators.extend(from_spec);
+                        marginal = spec_marginal;
+                    }
+                    LimitPrecedence::Code if !validators.iter().any(|v| v.is_limit()) => {
+                        validators.extend(from_spec);
+                        marginal = marginal.or(spec_marginal);
+                    }
+                    LimitPrecedence::Code => {}
+                }
+            }
+
+            // Limits with units can only be converted once the unit is known
+            let validators = validators
+                .into_iter()
+                .map(|v| v.resolve(def.unit.as_ref()))
+                .collect::<Vec<_>>();
+            def.validators.extend(validators);
+            def.transforms.extend(self.transforms);
+            def.marginal = marginal;
+        }
+
+        (self.manager, self.name)
+    }
+
+    fn set_internal(
+        self,
+        value: DataTypes,
+        location: &'static Location<'static>,
+    ) -> Result<(), TestFailure> {
+        let (manager, name) = self.update_definition();
+        manager.set_value_internal(&name, value, location)
     }
 
     #[track_caller]
     pub fn set(self, value: f64) -> Result<(), TestFailure> {
-        self.set_internal(DataTypes::F64(value))
+        self.set_internal(DataTypes::F64(value), Location::caller())
     }
 
+    #[track_caller]
     pub fn set_str(self, value: impl Into<String>) -> Result<(), TestFailure> {
-        self.set_internal(DataTypes::String(value.into()))
+        self.set_internal(DataTypes::String(value.into()), Location::caller())
+    }
+
+    #[track_caller]
+    pub fn set_i64(self, value: i64) -> Result<(), TestFailure> {
+        self.set_internal(DataTypes::I64(value), Location::caller())
+    }
+
+    #[track_caller]
+    pub fn set_u64(self, value: u64) -> Result<(), TestFailure> {
+        self.set_internal(DataTypes::U64(value), Location::caller())
+    }
+
+    #[track_caller]
+    pub fn set_bool(self, value: bool) -> Result<(), TestFailure> {
+        self.set_internal(DataTypes::Bool(value), Location::caller())
+    }
+
+    /// Fails the measurement if `value` isn't one of `allowed`
+    #[track_caller]
+    pub fn set_enum<S: Into<String>>(
+        self,
+        value: impl Into<String>,
+        allowed: impl IntoIterator<Item = S>,
+    ) -> Result<(), TestFailure> {
+        self.set_internal(enum_value(value, allowed), Location::caller())
+    }
+
+    #[track_caller]
+    pub fn set_duration(self, value: Duration) -> Result<(), TestFailure> {
+        self.set_internal(DataTypes::Duration(value), Location::caller())
+    }
+
+    #[track_caller]
+    pub fn set_bytes(self, value: impl Into<Vec<u8>>) -> Result<(), TestFailure> {
+        self.set_internal(DataTypes::Bytes(value.into()), Location::caller())
+    }
+
+    /// Sets repeated samples of the measurement. Validators can check a
+    /// statistic of them with [`validators::Aggregate`], or a transform such as
+    /// [`Self::mean`] can reduce them to one value.
+    #[track_caller]
+    pub fn set_samples(self, samples: impl AsRef<[f64]>) -> Result<(), TestFailure> {
+        let value = DataTypes::Samples(samples.as_ref().to_vec());
+        self.set_internal(value, Location::caller())
+    }
+
+    /// Sets a dimensioned measurement from rows of `(x, y...)` values, see
+    /// [`Self::with_axes`]
+    #[track_caller]
+    pub fn set_rows<R: Into<Vec<f64>>>(
+        self,
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<(), TestFailure> {
+        let value = self.dimensioned(rows);
+        self.set_internal(value, Location::caller())
+    }
+
+    fn dimensioned<R: Into<Vec<f64>>>(&self, rows: impl IntoIterator<Item = R>) -> DataTypes {
+        let rows: Vec<Vec<f64>> = rows.into_iter().map(Into::into).collect();
+
+        // Unnamed axes default to x, y1, y2...
+        let axes = if self.axes.is_empty() {
+            let width = rows.first().map_or(2, Vec::len);
+            (0..width)
+                .map(|i| match i {
+                    0 => Axis::new("x"),
+                    i => Axis::new(format!("y{}", i)),
+                })
+                .collect()
+        } else {
+            self.axes.clone()
+        };
+
+        DataTypes::Dimensioned { axes, rows }
+    }
+}
+
+macro_rules! impl_from_data_type {
+    ($($ty:ty => $variant:ident),* $(,)?) => {
+        $(
+            impl From<$ty> for DataTypes {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from_data_type!(
+    f64 => F64,
+    i64 => I64,
+    u64 => U64,
+    bool => Bool,
+    Duration => Duration,
+    Vec<u8> => Bytes,
+    String => String,
+    &str => String,
+);
+
+fn enum_value<S: Into<String>>(
+    value: impl Into<String>,
+    allowed: impl IntoIterator<Item = S>,
+) -> DataTypes {
+    DataTypes::Enum {
+        value: value.into(),
+        allowed: allowed.into_iter().map(Into::into).collect(),
     }
 }
 
@@ -133,7 +832,205 @@ impl std::fmt::Display for DataTypes {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::F64(v) => write!(f, "{}", v),
+            Self::I64(v) => write!(f, "{}", v),
+            Self::U64(v) => write!(f, "{}", v),
+            Self::Bool(v) => write!(f, "{}", v),
+            Self::Enum { value, .. } => write!(f, "{}", value),
+            Self::Duration(v) => write!(f, "{:.3?}", v),
+            Self::Bytes(v) => write!(f, "{}", hex(v)),
             Self::String(v) => write!(f, "{}", v),
+            Self::Dimensioned { axes, rows } => {
+                let names: Vec<&str> = axes.iter().map(|a| a.name.as_str()).collect();
+                write!(f, "{} points ({})", rows.len(), names.join(", "))
+            }
+            Self::Samples(samples) => write!(f, "{} samples", samples.len()),
         }
     }
 }
+
+/// Formats bytes as `0x` prefixed hex, used wherever byte values are shown
+pub fn hex(bytes: &[u8]) -> String {
+    let digits: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("0x{}", digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        common::*,
+        record::StationInfo,
+        test_runner::data::suite::{SuiteData, SuiteDataCollection},
+    };
+
+    /// The events have to be received for writes to succeed
+    fn measurements() -> (Measurements, UnboundedReceiver<Event>) {
+        let (event_tx, event_rx) = unbounded_channel();
+        let suites = vec![SuiteData::new(vec![("test", &[])], "Suite", 0)];
+        let data = SuiteDataCollection::new(suites, StationInfo::default(), None, event_tx);
+        (Measurements::new(TestHandle::new(data, 0, 0)), event_rx)
+    }
+
+    #[test]
+    fn statistics_of_known_samples() {
+        let stats = Statistics::of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]).unwrap();
+        assert_eq!(stats.count, 8);
+        assert_eq!(stats.mean, 5.0);
+        assert_eq!((stats.min, stats.max), (2.0, 9.0));
+        // Sample standard deviation, sqrt(32 / 7)
+        assert!((stats.stddev - 2.138).abs() < 1e-3, "{}", stats.stddev);
+
+        let single = Statistics::of(&[3.3]).unwrap();
+        assert_eq!((single.mean, single.stddev), (3.3, 0.0));
+        assert_eq!(Statistics::of(&[]), None);
+    }
+
+    #[test]
+    fn every_broken_rule_is_reported() {
+        let (mut measurements, _events) = measurements();
+
+        let failure = measurements
+            .measure("Rail")
+            .in_range(0.0, 5.0)
+            .at_most(4.0)
+            .at_least(1.0)
+            .set(7.0)
+            .unwrap_err();
+
+        let TestFailure::MeasurementFailed { failures, .. } = failure else {
+            panic!("expected a measurement failure, got {:?}", failure);
+        };
+        let rules: Vec<&str> = failures.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(rules, ["in range 0 to 5", "at most 4"]);
+    }
+
+    #[test]
+    fn file_limits_replace_one_sided_validators() {
+        let (mut measurements, _events) = measurements();
+        let spec = LimitSpec {
+            min: Some(3.0),
+            max: Some(6.0),
+            ..Default::default()
+        };
+        measurements.set_limits(
+            HashMap::from([("Rail".to_string(), spec)]),
+            LimitPrecedence::File,
+        );
+
+        let result = measurements
+            .measure("Rail")
+            .validate_with(AtLeast(5.0))
+            .validate_with(AtMost(4.0))
+            .set(4.5);
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn code_limits_win_over_file_limits() {
+        let (mut measurements, _events) = measurements();
+        let spec = LimitSpec {
+            min: Some(3.0),
+            max: Some(6.0),
+            marginal: Some([4.0, 5.0]),
+            ..Default::default()
+        };
+        measurements.set_limits(
+            HashMap::from([
+                ("Rail".to_string(), spec.clone()),
+                ("Bare".to_string(), spec),
+            ]),
+            LimitPrecedence::Code,
+        );
+
+        let failure = measurements
+            .measure("Rail")
+            .at_most(4.0)
+            .set(4.5)
+            .unwrap_err();
+        let TestFailure::MeasurementFailed { failures, .. } = failure else {
+            panic!("expected a measurement failure, got {:?}", failure);
+        };
+        let rules: Vec<&str> = failures.iter().map(|f| f.rule.as_str()).collect();
+        assert_eq!(rules, ["at most 4"]);
+
+        // Without limits in code the file's are used
+        assert!(measurements.measure("Bare").set(7.0).is_err());
+        measurements.measure("Bare").set(5.5).unwrap();
+        let status = measurements
+            .test
+            .write(|test, _| test.user_data["Bare"].status)
+            .unwrap();
+        assert_eq!(status, MeasurementStatus::Marginal);
+    }
+
+    #[test]
+    fn limits_are_compared_in_the_measurements_unit() {
+        let (mut measurements, _events) = measurements();
+        let rail = |measurements: &mut Measurements, value| {
+            measurements
+                .measure("Rail")
+                .with_unit("mV")
+                .in_range("3.0 V", "3.6 V")
+                .set(value)
+        };
+
+        assert_eq!(rail(&mut measurements, 3300.0), Ok(()));
+
+        let failure = rail(&mut measurements, 3.3).unwrap_err();
+        let TestFailure::MeasurementFailed { failures, .. } = failure else {
+            panic!("expected a measurement failure, got {:?}", failure);
+        };
+        assert_eq!(failures[0].rule, "in range 3000 to 3600");
+        assert_eq!(failures[0].reason, "found 3.3");
+    }
+
+    #[test]
+    fn marginal_range_converts_units() {
+        let (mut measurements, _events) = measurements();
+
+        measurements
+            .measure("Rail")
+            .with_unit("mV")
+            .in_range("3.0 V", "3.6 V")
+            .marginal_range("3.2 V", "3.4 V")
+            .set(3450.0)
+            .unwrap();
+
+        let def = measurements
+            .test
+            .write(|test, _| test.user_data["Rail"].clone())
+            .unwrap();
+        assert_eq!(def.marginal, Some((3200.0, 3400.0)));
+        assert_eq!(def.status, MeasurementStatus::Marginal);
+    }
+
+    #[test]
+    fn samples_and_points_can_be_marginal() {
+        let (mut measurements, _events) = measurements();
+        let mut status =
+            |name: &str, set: &dyn Fn(MeasurementSetter) -> Result<(), TestFailure>| {
+                set(measurements.measure(name).marginal_range(1.0, 2.0)).unwrap();
+                measurements
+                    .test
+                    .write(|test, _| test.user_data[name].status)
+                    .unwrap()
+            };
+
+        assert_eq!(
+            status("Inside", &|m| m.set_samples([1.2, 1.5, 1.8])),
+            MeasurementStatus::Pass
+        );
+        assert_eq!(
+            status("Noisy", &|m| m.set_samples([1.2, 2.5, 1.8])),
+            MeasurementStatus::Marginal
+        );
+        assert_eq!(
+            status("Averaged", &|m| m.mean().set_samples([0.5, 2.5])),
+            MeasurementStatus::Pass
+        );
+        assert_eq!(
+            status("Sweep", &|m| m.set_rows([[10.0, 1.5], [20.0, 0.5]])),
+            MeasurementStatus::Marginal
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use super::unit::MeasurementUnit;
+use super::DataTypes;
+
+/// A step applied to a measured value before it's validated, e.g. a
+/// calibration. Transforms are chained with
+/// [`super::MeasurementSetter::transform`] and run in the order they were
+/// added.
+pub trait Transform: Send + Sync {
+    /// Names the step in failures and reports, e.g. `scale by 0.5`
+    fn describe(&self) -> String;
+
+    /// Returns the transformed value, or why the value can't be transformed
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String>;
+}
+
+impl std::fmt::Debug for dyn Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Transform({})", self.describe())
+    }
+}
+
+/// Applies `f` to every number in the value. Single numbers come out as
+/// `f64`, dimensioned values only have their `y` values changed.
+fn map_numbers(value: DataTypes, f: impl Fn(f64) -> f64) -> Result<DataTypes, String> {
+    Ok(match value {
+        DataTypes::Dimensioned { axes, rows } => DataTypes::Dimensioned {
+            axes,
+            rows: rows
+                .into_iter()
+                .map(|row| {
+                    row.iter()
+                        .enumerate()
+                        .map(|(i, v)| if i == 0 { *v } else { f(*v) })
+                        .collect()
+                })
+                .collect(),
+        },
+        DataTypes::Samples(samples) => DataTypes::Samples(samples.into_iter().map(f).collect()),
+        value => match value.as_f64() {
+            Some(v) => DataTypes::F64(f(v)),
+            None => {
+                return Err(format!(
+                    "expected a numeric value, found {}",
+                    value.type_name()
+                ))
+            }
+        },
+    })
+}
+
+/// Multiplies the value, e.g. by a calibration gain
+pub struct Scale(pub f64);
+
+impl Transform for Scale {
+    fn describe(&self) -> String {
+        format!("scale by {}", self.0)
+    }
+
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String> {
+        map_numbers(value, |v| v * self.0)
+    }
+}
+
+/// Adds to the value, e.g. a calibration offset
+pub struct Offset(pub f64);
+
+impl Transform for Offset {
+    fn describe(&self) -> String {
+        format!("offset by {}", self.0)
+    }
+
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String> {
+        map_numbers(value, |v| v + self.0)
+    }
+}
+
+pub struct ConvertUnit {
+    from: MeasurementUnit,
+    to: MeasurementUnit,
+}
+
+impl ConvertUnit {
+    pub fn new(from: impl Into<MeasurementUnit>, to: impl Into<MeasurementUnit>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+}
+
+impl Transform for ConvertUnit {
+    fn describe(&self) -> String {
+        format!("convert {} to {}", self.from, self.to)
+    }
+
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String> {
+        let (Some(from), Some(to)) = (self.from.scaled(), self.to.scaled()) else {
+            return Err(format!("can't convert {} to {}", self.from, self.to));
+        };
+        if to.convert(1.0, from).is_none() {
+            return Err(format!("can't convert {} to {}", from, to));
+        }
+
+        map_numbers(value, |v| to.convert(v, from).unwrap())
+    }
+}
+
+/// Averages a vector of samples into a single value
+pub struct Mean;
+
+impl Transform for Mean {
+    fn describe(&self) -> String {
+        "mean".into()
+    }
+
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String> {
+        match value {
+            DataTypes::Samples(samples) if samples.is_empty() => Err("no samples".into()),
+            DataTypes::Samples(samples) => Ok(DataTypes::F64(
+                samples.iter().sum::<f64>() / samples.len() as f64,
+            )),
+            value => Err(format!("expected samples, found {}", value.type_name())),
+        }
+    }
+}
+
+/// A user defined step from a closure
+pub struct Map<F> {
+    description: String,
+    f: F,
+}
+
+impl<F> Map<F>
+where
+    F: Fn(DataTypes) -> Result<DataTypes, String> + Send + Sync,
+{
+    pub fn new(description: impl Into<String>, f: F) -> Self {
+        Self {
+            description: description.into(),
+            f,
+        }
+    }
+}
+
+impl<F> Transform for Map<F>
+where
+    F: Fn(DataTypes) -> Result<DataTypes, String> + Send + Sync,
+{
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+
+    fn apply(&self, value: DataTypes) -> Result<DataTypes, String> {
+        (self.f)(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::context::measurement::Axis;
+
+    #[test]
+    fn scale_and_offset() {
+        assert_eq!(Scale(0.5).describe(), "scale by 0.5");
+        assert_eq!(Scale(0.5).apply(4i64.into()), Ok(DataTypes::F64(2.0)));
+        assert_eq!(Offset(-1.0).describe(), "offset by -1");
+        assert_eq!(
+            Offset(-1.0).apply(DataTypes::Samples(vec![1.0, 2.0])),
+            Ok(DataTypes::Samples(vec![0.0, 1.0]))
+        );
+        assert_eq!(
+            Scale(2.0).apply("high".into()),
+            Err("expected a numeric value, found string".into())
+        );
+
+        // Only the y values of a sweep are changed
+        let axes = vec![Axis::new("freq"), Axis::new("gain")];
+        let sweep = DataTypes::Dimensioned {
+            axes: axes.clone(),
+            rows: vec![vec![10.0, 1.0], vec![20.0, 3.0]],
+        };
+        assert_eq!(
+            Scale(2.0).apply(sweep),
+            Ok(DataTypes::Dimensioned {
+                axes,
+                rows: vec![vec![10.0, 2.0], vec![20.0, 6.0]],
+            })
+        );
+    }
+
+    #[test]
+    fn convert_unit() {
+        let to_millivolts = ConvertUnit::new("V", "mV");
+        assert_eq!(to_millivolts.describe(), "convert V to mV");
+        assert_eq!(to_millivolts.apply(1.89.into()), Ok(DataTypes::F64(1890.0)));
+
+        assert_eq!(
+            ConvertUnit::new("V", "A").apply(1.0.into()),
+            Err("can't convert V to A".into())
+        );
+        assert_eq!(
+            ConvertUnit::new("V", "RPM").apply(1.0.into()),
+            Err("can't convert V to RPM".into())
+        );
+    }
+
+    #[test]
+    fn mean() {
+        assert_eq!(Mean.describe(), "mean");
+        assert_eq!(
+            Mean.apply(DataTypes::Samples(vec![1.0, 2.0, 6.0])),
+            Ok(DataTypes::F64(3.0))
+        );
+        assert_eq!(
+            Mean.apply(DataTypes::Samples(vec![])),
+            Err("no samples".into())
+        );
+        assert_eq!(
+            Mean.apply(1.0.into()),
+            Err("expected samples, found f64".into())
+        );
+    }
+}
@@ -0,0 +1,393 @@
+use std::str::FromStr;
+
+/// SI base units and the derived units common on a test bench
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Second,
+    Metre,
+    Gram,
+    Ampere,
+    Kelvin,
+    Mole,
+    Candela,
+    Volt,
+    Ohm,
+    Siemens,
+    Hertz,
+    Watt,
+    Joule,
+    Coulomb,
+    Farad,
+    Henry,
+    Celsius,
+    Decibel,
+    DecibelMilliwatt,
+    Percent,
+}
+
+const UNITS: [(Unit, &str, &[&str]); 20] = [
+    (Unit::Second, "s", &["second", "seconds", "sec"]),
+    (Unit::Metre, "m", &["metre", "metres", "meter", "meters"]),
+    (Unit::Gram, "g", &["gram", "grams"]),
+    (Unit::Ampere, "A", &["ampere", "amperes", "amp", "amps"]),
+    (Unit::Kelvin, "K", &["kelvin"]),
+    (Unit::Mole, "mol", &["mole", "moles"]),
+    (Unit::Candela, "cd", &["candela"]),
+    (Unit::Volt, "V", &["volt", "volts"]),
+    (Unit::Ohm, "Ω", &["ohm", "ohms"]),
+    (Unit::Siemens, "S", &["siemens"]),
+    (Unit::Hertz, "Hz", &["hertz"]),
+    (Unit::Watt, "W", &["watt", "watts"]),
+    (Unit::Joule, "J", &["joule", "joules"]),
+    (Unit::Coulomb, "C", &["coulomb", "coulombs"]),
+    (Unit::Farad, "F", &["farad", "farads"]),
+    (Unit::Henry, "H", &["henry", "henries"]),
+    (Unit::Celsius, "°C", &["celsius", "degc"]),
+    (Unit::Decibel, "dB", &["decibel", "decibels"]),
+    (Unit::DecibelMilliwatt, "dBm", &[]),
+    (Unit::Percent, "%", &["percent"]),
+];
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        UNITS
+            .iter()
+            .find(|(unit, _, _)| unit == self)
+            .map(|(_, symbol, _)| *symbol)
+            .unwrap()
+    }
+
+    pub const fn prefixed(self, prefix: Prefix) -> ScaledUnit {
+        ScaledUnit { prefix, unit: self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Prefix {
+    Pico,
+    Nano,
+    Micro,
+    Milli,
+    #[default]
+    None,
+    Kilo,
+    Mega,
+    Giga,
+}
+
+const PREFIXES: [(Prefix, &str, i32); 7] = [
+    (Prefix::Pico, "p", -12),
+    (Prefix::Nano, "n", -9),
+    (Prefix::Micro, "µ", -6),
+    (Prefix::Milli, "m", -3),
+    (Prefix::Kilo, "k", 3),
+    (Prefix::Mega, "M", 6),
+    (Prefix::Giga, "G", 9),
+];
+
+impl Prefix {
+    pub fn symbol(&self) -> &'static str {
+        PREFIXES
+            .iter()
+            .find(|(prefix, _, _)| prefix == self)
+            .map_or("", |(_, symbol, _)| *symbol)
+    }
+
+    /// The power of ten the prefix stands for
+    pub fn exponent(&self) -> i32 {
+        PREFIXES
+            .iter()
+            .find(|(prefix, _, _)| prefix == self)
+            .map_or(0, |(_, _, exponent)| *exponent)
+    }
+}
+
+/// A unit with an SI prefix, e.g. `mV` or `kHz`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScaledUnit {
+    pub prefix: Prefix,
+    pub unit: Unit,
+}
+
+impl ScaledUnit {
+    /// Converts `value` given in `from` into this unit, `None` if the units
+    /// measure different things
+    pub fn convert(&self, value: f64, from: ScaledUnit) -> Option<f64> {
+        if self.unit != from.unit {
+            return None;
+        }
+
+        // Scaling by a whole power of ten keeps e.g. 1.89 V at exactly 1890 mV
+        let shift = from.prefix.exponent() - self.prefix.exponent();
+        Some(match shift {
+            0.. => value * 10f64.powi(shift),
+            _ => value / 10f64.powi(-shift),
+        })
+    }
+}
+
+impl FromStr for ScaledUnit {
+    type Err = String;
+
+    /// Parses symbols such as `mV`, `kHz`, `ohm` or `us` and full names
+    /// such as `Volts`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lower = s.to_lowercase();
+
+        let by_name = UNITS
+            .iter()
+            .find(|(_, symbol, names)| *symbol == s || names.contains(&lower.as_str()));
+        if let Some((unit, _, _)) = by_name {
+            return Ok(unit.prefixed(Prefix::None));
+        }
+
+        for (prefix, prefix_symbol, _) in PREFIXES {
+            let rest = s.strip_prefix(prefix_symbol).or_else(|| {
+                (prefix == Prefix::Micro)
+                    .then(|| s.strip_prefix('u'))
+                    .flatten()
+            });
+            let Some(rest) = rest.filter(|r| !r.is_empty()) else {
+                continue;
+            };
+            let rest_lower = rest.to_lowercase();
+            if let Some((unit, _, _)) = UNITS
+                .iter()
+                .find(|(_, symbol, names)| *symbol == rest || names.contains(&rest_lower.as_str()))
+            {
+                return Ok(unit.prefixed(prefix));
+            }
+        }
+
+        Err(format!("unknown unit '{}'", s))
+    }
+}
+
+impl From<Unit> for ScaledUnit {
+    fn from(unit: Unit) -> Self {
+        unit.prefixed(Prefix::None)
+    }
+}
+
+impl std::fmt::Display for ScaledUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.prefix.symbol(), self.unit.symbol())
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// The unit of a measurement. Strings that name a known unit are parsed so
+/// they can be converted and render the same everywhere, anything else, e.g.
+/// `RPM`, is kept as written.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeasurementUnit {
+    Scaled(ScaledUnit),
+    Other(String),
+}
+
+impl MeasurementUnit {
+    pub fn scaled(&self) -> Option<ScaledUnit> {
+        match self {
+            Self::Scaled(unit) => Some(*unit),
+            Self::Other(_) => None,
+        }
+    }
+}
+
+impl From<&str> for MeasurementUnit {
+    fn from(unit: &str) -> Self {
+        match unit.parse() {
+            Ok(unit) => Self::Scaled(unit),
+            Err(_) => Self::Other(unit.to_string()),
+        }
+    }
+}
+
+impl From<String> for MeasurementUnit {
+    fn from(unit: String) -> Self {
+        unit.as_str().into()
+    }
+}
+
+impl From<Unit> for MeasurementUnit {
+    fn from(unit: Unit) -> Self {
+        Self::Scaled(unit.into())
+    }
+}
+
+impl From<ScaledUnit> for MeasurementUnit {
+    fn from(unit: ScaledUnit) -> Self {
+        Self::Scaled(unit)
+    }
+}
+
+impl std::fmt::Display for MeasurementUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scaled(unit) => write!(f, "{}", unit),
+            Self::Other(unit) => write!(f, "{}", unit),
+        }
+    }
+}
+
+/// A value with a unit, e.g. `"3.3 V"` or `(3.3, Unit::Volt)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: ScaledUnit,
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let split = s
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E')))
+            .ok_or_else(|| format!("'{}' has no unit", s))?;
+        let (value, unit) = s.split_at(split);
+
+        Ok(Self {
+            value: value
+                .trim()
+                .parse()
+                .map_err(|_| format!("'{}' is not a number", value.trim()))?,
+            unit: unit.parse()?,
+        })
+    }
+}
+
+impl<U: Into<ScaledUnit>> From<(f64, U)> for Quantity {
+    fn from((value, unit): (f64, U)) -> Self {
+        Self {
+            value,
+            unit: unit.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+/// A limit for [`super::MeasurementSetter::in_range`] and friends. Plain
+/// numbers are in the measurement's own unit, quantities are converted to it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Limit {
+    Plain(f64),
+    Quantity(Quantity),
+    /// A string that didn't parse as a quantity, fails the measurement
+    Invalid(String),
+}
+
+impl Limit {
+    /// The limit in `unit`, the measurement's unit
+    pub fn resolve(&self, unit: Option<&MeasurementUnit>) -> Result<f64, String> {
+        match self {
+            Self::Plain(value) => Ok(*value),
+            Self::Quantity(quantity) => {
+                let Some(target) = unit.and_then(MeasurementUnit::scaled) else {
+                    return Err(format!(
+                        "can't compare {} with a measurement in {}",
+                        quantity,
+                        unit.map_or("no unit".to_string(), |u| format!("'{}'", u))
+                    ));
+                };
+                target
+                    .convert(quantity.value, quantity.unit)
+                    .ok_or_else(|| format!("can't convert {} to {}", quantity, target))
+            }
+            Self::Invalid(reason) => Err(reason.clone()),
+        }
+    }
+}
+
+impl From<f64> for Limit {
+    fn from(value: f64) -> Self {
+        Self::Plain(value)
+    }
+}
+
+impl From<Quantity> for Limit {
+    fn from(quantity: Quantity) -> Self {
+        Self::Quantity(quantity)
+    }
+}
+
+impl<U: Into<ScaledUnit>> From<(f64, U)> for Limit {
+    fn from(quantity: (f64, U)) -> Self {
+        Self::Quantity(quantity.into())
+    }
+}
+
+impl From<&str> for Limit {
+    fn from(quantity: &str) -> Self {
+        match quantity.parse() {
+            Ok(quantity) => Self::Quantity(quantity),
+            Err(reason) => Self::Invalid(reason),
+        }
+    }
+}
+
+impl std::fmt::Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Plain(value) => write!(f, "{}", value),
+            Self::Quantity(quantity) => write!(f, "{}", quantity),
+            Self::Invalid(_) => write!(f, "?"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefixed_units_parse() {
+        let millivolt = Unit::Volt.prefixed(Prefix::Milli);
+        for s in ["mV", "mVolts", "mvolt", " mV "] {
+            assert_eq!(s.parse::<ScaledUnit>(), Ok(millivolt), "{}", s);
+        }
+
+        assert_eq!("kHz".parse(), Ok(Unit::Hertz.prefixed(Prefix::Kilo)));
+        assert_eq!("us".parse(), Ok(Unit::Second.prefixed(Prefix::Micro)));
+        assert_eq!("µs".parse(), Ok(Unit::Second.prefixed(Prefix::Micro)));
+        assert_eq!("ms".parse(), Ok(Unit::Second.prefixed(Prefix::Milli)));
+        assert_eq!("m".parse(), Ok(ScaledUnit::from(Unit::Metre)));
+        assert_eq!("Volts".parse(), Ok(ScaledUnit::from(Unit::Volt)));
+        assert!("RPM".parse::<ScaledUnit>().is_err());
+    }
+
+    #[test]
+    fn limits_convert_to_the_measurement_unit() {
+        let millivolts = MeasurementUnit::from("mV");
+        assert_eq!(Limit::from("3.0 V").resolve(Some(&millivolts)), Ok(3000.0));
+        assert_eq!(Limit::from("3.6 V").resolve(Some(&millivolts)), Ok(3600.0));
+        assert_eq!(Limit::from("1.89 V").resolve(Some(&millivolts)), Ok(1890.0));
+        assert_eq!(Limit::from(3300.0).resolve(Some(&millivolts)), Ok(3300.0));
+        assert_eq!(
+            Limit::from((2.0, Unit::Ampere)).resolve(Some(&millivolts)),
+            Err("can't convert 2 A to mV".into())
+        );
+        assert_eq!(
+            Limit::from("3 V").resolve(None),
+            Err("can't compare 3 V with a measurement in no unit".into())
+        );
+        assert_eq!(
+            Limit::from("3 V").resolve(Some(&"RPM".into())),
+            Err("can't compare 3 V with a measurement in 'RPM'".into())
+        );
+        assert!(Limit::from("three volts")
+            .resolve(Some(&millivolts))
+            .is_err());
+    }
+}
@@ -0,0 +1,477 @@
+use regex::Regex;
+
+use super::{DataTypes, Statistics};
+
+/// A rule a measured value must satisfy. Any number of validators can be
+/// attached to a measurement with [`super::MeasurementSetter::validate_with`],
+/// every one of them is checked when the value is set.
+pub trait Validator: Send + Sync {
+    /// Names the rule in failures and reports, e.g. `in range 3.0 to 3.6`
+    fn describe(&self) -> String;
+
+    /// Returns why `value` breaks the rule
+    fn validate(&self, value: &DataTypes) -> Result<(), String>;
+
+    /// Numeric spec limits implied by the rule, shown alongside the
+    /// measurement in reports
+    fn limits(&self) -> Option<(f64, f64)> {
+        None
+    }
+
+    /// Whether the rule is a numeric limit, which limits from the limits file
+    /// replace, see [`crate::LimitPrecedence`]
+    fn is_limit(&self) -> bool {
+        self.limits().is_some()
+    }
+}
+
+impl std::fmt::Debug for dyn Validator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Validator({})", self.describe())
+    }
+}
+
+fn numeric(value: &DataTypes) -> Result<f64, String> {
+    value
+        .as_f64()
+        .ok_or_else(|| format!("expected a numeric value, found {}", value.type_name()))
+}
+
+pub struct InRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Validator for InRange {
+    fn describe(&self) -> String {
+        format!("in range {} to {}", self.min, self.max)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let found = numeric(value)?;
+        if found < self.min || found > self.max {
+            return Err(format!("found {}", found));
+        }
+        Ok(())
+    }
+
+    fn limits(&self) -> Option<(f64, f64)> {
+        Some((self.min, self.max))
+    }
+}
+
+pub struct AtLeast(pub f64);
+
+impl Validator for AtLeast {
+    fn describe(&self) -> String {
+        format!("at least {}", self.0)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let found = numeric(value)?;
+        if found < self.0 {
+            return Err(format!("found {}", found));
+        }
+        Ok(())
+    }
+
+    /// Still a limit, although with one side there's no range to report
+    fn is_limit(&self) -> bool {
+        true
+    }
+}
+
+pub struct AtMost(pub f64);
+
+impl Validator for AtMost {
+    fn describe(&self) -> String {
+        format!("at most {}", self.0)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let found = numeric(value)?;
+        if found > self.0 {
+            return Err(format!("found {}", found));
+        }
+        Ok(())
+    }
+
+    /// Still a limit, although with one side there's no range to report
+    fn is_limit(&self) -> bool {
+        true
+    }
+}
+
+/// Numeric values are compared by value, so `Equals(5.0.into())` accepts
+/// an `i64` of 5
+pub struct Equals(pub DataTypes);
+
+impl Validator for Equals {
+    fn describe(&self) -> String {
+        format!("equals {}", self.0)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let equal = match (self.0.as_f64(), value.as_f64()) {
+            (Some(expected), Some(found)) => expected == found,
+            _ => self.0 == *value,
+        };
+
+        if !equal {
+            return Err(format!("found {}", value));
+        }
+        Ok(())
+    }
+}
+
+pub struct WithinPercent {
+    pub nominal: f64,
+    pub percent: f64,
+}
+
+impl Validator for WithinPercent {
+    fn describe(&self) -> String {
+        format!("within {}% of {}", self.percent, self.nominal)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let found = numeric(value)?;
+        let (min, max) = self.limits().unwrap();
+        if found < min || found > max {
+            return Err(format!("found {}", found));
+        }
+        Ok(())
+    }
+
+    fn limits(&self) -> Option<(f64, f64)> {
+        let tolerance = (self.nominal * self.percent / 100.0).abs();
+        Some((self.nominal - tolerance, self.nominal + tolerance))
+    }
+}
+
+/// Matches string and enum values against a regular expression
+pub struct Matches {
+    pattern: String,
+    regex: Result<Regex, regex::Error>,
+}
+
+impl Matches {
+    pub fn new(pattern: impl Into<String>) -> Self {
+        let pattern = pattern.into();
+        Self {
+            regex: Regex::new(&pattern),
+            pattern,
+        }
+    }
+}
+
+impl Validator for Matches {
+    fn describe(&self) -> String {
+        format!("matches /{}/", self.pattern)
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let regex = self.regex.as_ref().map_err(|e| e.to_string())?;
+
+        let text = match value {
+            DataTypes::String(s) => s,
+            DataTypes::Enum { value, .. } => value,
+            _ => return Err(format!("expected a string, found {}", value.type_name())),
+        };
+
+        if !regex.is_match(text) {
+            return Err(format!("found '{}'", text));
+        }
+        Ok(())
+    }
+}
+
+pub struct OneOf(pub Vec<DataTypes>);
+
+impl Validator for OneOf {
+    fn describe(&self) -> String {
+        let options: Vec<String> = self.0.iter().map(|v| v.to_string()).collect();
+        format!("one of [{}]", options.join(", "))
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        if !self
+            .0
+            .iter()
+            .any(|option| Equals(option.clone()).validate(value).is_ok())
+        {
+            return Err(format!("found {}", value));
+        }
+        Ok(())
+    }
+}
+
+/// Checks each `y` value of a dimensioned measurement, other values are
+/// checked as they are
+pub struct EachPoint(Box<dyn Validator>);
+
+impl EachPoint {
+    pub fn new(validator: impl Validator + 'static) -> Self {
+        Self(Box::new(validator))
+    }
+}
+
+impl Validator for EachPoint {
+    fn describe(&self) -> String {
+        format!("each point {}", self.0.describe())
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let DataTypes::Dimensioned { axes, rows } = value else {
+            return self.0.validate(value);
+        };
+
+        for row in rows {
+            let Some((x, ys)) = row.split_first() else {
+                continue;
+            };
+            for (axis, y) in axes.iter().skip(1).zip(ys) {
+                self.0.validate(&DataTypes::F64(*y)).map_err(|reason| {
+                    format!("{} at {} = {}: {}", axis.name, axes[0].name, x, reason)
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn limits(&self) -> Option<(f64, f64)> {
+        self.0.limits()
+    }
+
+    fn is_limit(&self) -> bool {
+        self.0.is_limit()
+    }
+}
+
+/// Checks a single statistic of repeated samples, or of all `y` values of a
+/// dimensioned measurement, e.g. `Aggregate::max(AtMost(3.0))`
+pub struct Aggregate {
+    name: &'static str,
+    statistic: fn(&Statistics) -> f64,
+    validator: Box<dyn Validator>,
+}
+
+impl Aggregate {
+    pub fn min(validator: impl Validator + 'static) -> Self {
+        Self::new("min", |s| s.min, validator)
+    }
+
+    pub fn max(validator: impl Validator + 'static) -> Self {
+        Self::new("max", |s| s.max, validator)
+    }
+
+    pub fn mean(validator: impl Validator + 'static) -> Self {
+        Self::new("mean", |s| s.mean, validator)
+    }
+
+    pub fn stddev(validator: impl Validator + 'static) -> Self {
+        Self::new("stddev", |s| s.stddev, validator)
+    }
+
+    fn new(
+        name: &'static str,
+        statistic: fn(&Statistics) -> f64,
+        validator: impl Validator + 'static,
+    ) -> Self {
+        Self {
+            name,
+            statistic,
+            validator: Box::new(validator),
+        }
+    }
+}
+
+impl Validator for Aggregate {
+    fn describe(&self) -> String {
+        format!("{} {}", self.name, self.validator.describe())
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        let values: Vec<f64> = match value {
+            DataTypes::Samples(samples) => samples.clone(),
+            DataTypes::Dimensioned { rows, .. } => rows
+                .iter()
+                .flat_map(|row| row.iter().skip(1))
+                .copied()
+                .collect(),
+            _ => {
+                return Err(format!(
+                    "expected samples or a dimensioned value, found {}",
+                    value.type_name()
+                ))
+            }
+        };
+        let Some(statistics) = Statistics::of(&values) else {
+            return Err("no values".into());
+        };
+
+        self.validator
+            .validate(&DataTypes::F64((self.statistic)(&statistics)))
+            .map_err(|reason| format!("{} {}", self.name, reason))
+    }
+}
+
+/// A user defined rule from a closure
+pub struct Custom<F> {
+    description: String,
+    check: F,
+}
+
+impl<F> Custom<F>
+where
+    F: Fn(&DataTypes) -> Result<(), String> + Send + Sync,
+{
+    pub fn new(description: impl Into<String>, check: F) -> Self {
+        Self {
+            description: description.into(),
+            check,
+        }
+    }
+}
+
+impl<F> Validator for Custom<F>
+where
+    F: Fn(&DataTypes) -> Result<(), String> + Send + Sync,
+{
+    fn describe(&self) -> String {
+        self.description.clone()
+    }
+
+    fn validate(&self, value: &DataTypes) -> Result<(), String> {
+        (self.check)(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_runner::context::measurement::Axis;
+
+    /// `validator`'s verdict on `value`, as it's shown in a failure
+    fn check(validator: &dyn Validator, value: impl Into<DataTypes>) -> Result<(), String> {
+        validator.validate(&value.into())
+    }
+
+    fn sweep(rows: Vec<Vec<f64>>) -> DataTypes {
+        DataTypes::Dimensioned {
+            axes: vec![Axis::new("freq"), Axis::new("gain")],
+            rows,
+        }
+    }
+
+    #[test]
+    fn numeric_limits() {
+        let in_range = InRange { min: 1.0, max: 2.0 };
+        assert_eq!(in_range.describe(), "in range 1 to 2");
+        assert_eq!(check(&in_range, 1.5), Ok(()));
+        assert_eq!(check(&in_range, 2.5), Err("found 2.5".into()));
+        assert_eq!(
+            check(&in_range, "high"),
+            Err("expected a numeric value, found string".into())
+        );
+
+        let at_least = AtLeast(1.0);
+        assert_eq!(at_least.describe(), "at least 1");
+        assert_eq!(check(&at_least, 1i64), Ok(()));
+        assert_eq!(check(&at_least, 0.5), Err("found 0.5".into()));
+
+        let at_most = AtMost(1.0);
+        assert_eq!(at_most.describe(), "at most 1");
+        assert_eq!(check(&at_most, 1u64), Ok(()));
+        assert_eq!(check(&at_most, 1.5), Err("found 1.5".into()));
+
+        let within = WithinPercent {
+            nominal: 10.0,
+            percent: 5.0,
+        };
+        assert_eq!(within.describe(), "within 5% of 10");
+        assert_eq!(within.limits(), Some((9.5, 10.5)));
+        assert_eq!(check(&within, 10.4), Ok(()));
+        assert_eq!(check(&within, 11.0), Err("found 11".into()));
+    }
+
+    #[test]
+    fn values() {
+        let equals = Equals(5.0.into());
+        assert_eq!(equals.describe(), "equals 5");
+        assert_eq!(check(&equals, 5i64), Ok(()));
+        assert_eq!(check(&equals, 4.0), Err("found 4".into()));
+        assert_eq!(check(&Equals("ok".into()), "ok"), Ok(()));
+
+        let matches = Matches::new("^SN-[0-9]+$");
+        assert_eq!(matches.describe(), "matches /^SN-[0-9]+$/");
+        assert_eq!(check(&matches, "SN-0001"), Ok(()));
+        assert_eq!(check(&matches, "0001"), Err("found '0001'".into()));
+        assert_eq!(
+            check(&matches, 1.0),
+            Err("expected a string, found f64".into())
+        );
+        assert!(check(&Matches::new("("), "(").is_err());
+
+        let one_of = OneOf(vec!["red".into(), "green".into()]);
+        assert_eq!(one_of.describe(), "one of [red, green]");
+        assert_eq!(check(&one_of, "green"), Ok(()));
+        assert_eq!(check(&one_of, "blue"), Err("found blue".into()));
+    }
+
+    #[test]
+    fn each_point() {
+        let each = EachPoint::new(AtLeast(0.0));
+        assert_eq!(each.describe(), "each point at least 0");
+        assert!(each.is_limit());
+        assert_eq!(
+            check(&each, sweep(vec![vec![1.0, 2.0], vec![2.0, 1.0]])),
+            Ok(())
+        );
+        assert_eq!(
+            check(&each, sweep(vec![vec![1.0, 2.0], vec![2.0, -1.0]])),
+            Err("gain at freq = 2: found -1".into())
+        );
+        assert_eq!(check(&each, -1.0), Err("found -1".into()));
+    }
+
+    #[test]
+    fn aggregate() {
+        let samples = DataTypes::Samples(vec![1.0, 2.0, 3.0]);
+
+        let max = Aggregate::max(AtMost(3.0));
+        assert_eq!(max.describe(), "max at most 3");
+        assert_eq!(max.validate(&samples), Ok(()));
+
+        let mean = Aggregate::mean(InRange { min: 0.0, max: 1.5 });
+        assert_eq!(mean.validate(&samples), Err("mean found 2".into()));
+
+        let min = Aggregate::min(AtLeast(0.0));
+        assert_eq!(
+            min.validate(&sweep(vec![vec![1.0, 2.0], vec![2.0, -1.0]])),
+            Err("min found -1".into())
+        );
+
+        let stddev = Aggregate::stddev(AtMost(0.5));
+        assert_eq!(stddev.validate(&samples), Err("stddev found 1".into()));
+        assert_eq!(
+            stddev.validate(&DataTypes::Samples(vec![])),
+            Err("no values".into())
+        );
+        assert_eq!(
+            check(&stddev, 1.0),
+            Err("expected samples or a dimensioned value, found f64".into())
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let even = Custom::new("even", |value: &DataTypes| match value {
+            DataTypes::I64(v) if v % 2 == 0 => Ok(()),
+            value => Err(format!("found {}", value)),
+        });
+        assert_eq!(even.describe(), "even");
+        assert_eq!(check(&even, 4i64), Ok(()));
+        assert_eq!(check(&even, 3i64), Err("found 3".into()));
+    }
+}
@@ -1,53 +1,493 @@
+use std::{ops::RangeBounds, time::Duration};
+
 use tokio::sync::oneshot;
 
 use crate::{
     common::*,
-    test_runner::{data::suite::SuiteDataCollection, TestRunning, TestState},
+    test_runner::{
+        data::{suite::TestHandle, OperatorInput, TestData},
+        TestRunning, TestState,
+    },
     TestFailure,
 };
 
+use super::wait;
+
+pub mod instructions;
+pub mod prompt;
+
+use instructions::Instructions;
+use prompt::{Answer, Prompt, PromptKind};
+
 pub struct TextInput {
     event_tx: UnboundedSender<Event>,
-    suites_data: SuiteDataCollection,
+    test: TestHandle,
 }
 
 impl TextInput {
-    pub fn new(event_tx: UnboundedSender<Event>, suites_data: SuiteDataCollection) -> Self {
-        Self {
-            event_tx,
-            suites_data,
+    pub fn new(event_tx: UnboundedSender<Event>, test: TestHandle) -> Self {
+        Self { event_tx, test }
+    }
+
+    /// Points prompts at the next test, set by the runner
+    pub(crate) fn set_test(&mut self, test: TestHandle) {
+        self.test = test;
+    }
+
+    /// Starts a prompt with options such as a timeout, e.g.
+    /// `prompt("Lid closed?").timeout(Duration::from_secs(30)).default_answer("y").confirm()`
+    pub fn prompt(&mut self, text: impl Into<String>) -> PromptBuilder<'_> {
+        PromptBuilder {
+            input: self,
+            text: text.into(),
+            id: None,
+            timeout: None,
+            default: None,
+            instructions: None,
         }
     }
 
     pub fn request(&mut self, prompt: impl Into<String>) -> Result<String, TestFailure> {
-        let prompt = prompt.into();
+        self.prompt(prompt).request()
+    }
+
+    pub async fn request_async(
+        &mut self,
+        prompt: impl Into<String>,
+    ) -> Result<String, TestFailure> {
+        self.prompt(prompt).request_async().await
+    }
+
+    /// Asks a yes or no question
+    pub fn confirm(&mut self, prompt: impl Into<String>) -> Result<bool, TestFailure> {
+        self.prompt(prompt).confirm()
+    }
+
+    pub async fn confirm_async(&mut self, prompt: impl Into<String>) -> Result<bool, TestFailure> {
+        self.prompt(prompt).confirm_async().await
+    }
 
-        let (input_tx, input_rx) = oneshot::channel::<String>();
+    /// Asks for a number in `range`, e.g. `0.0..=5.0` or `..` for any number
+    pub fn number(
+        &mut self,
+        prompt: impl Into<String>,
+        range: impl RangeBounds<f64>,
+    ) -> Result<f64, TestFailure> {
+        self.prompt(prompt).number(range)
+    }
+
+    pub async fn number_async(
+        &mut self,
+        prompt: impl Into<String>,
+        range: impl RangeBounds<f64>,
+    ) -> Result<f64, TestFailure> {
+        self.prompt(prompt).number_async(range).await
+    }
+
+    /// Asks for one of `options`
+    pub fn choose<S: Into<String>>(
+        &mut self,
+        prompt: impl Into<String>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<String, TestFailure> {
+        self.prompt(prompt).choose(options)
+    }
+
+    pub async fn choose_async<S: Into<String>>(
+        &mut self,
+        prompt: impl Into<String>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<String, TestFailure> {
+        self.prompt(prompt).choose_async(options).await
+    }
+
+    /// Asks for any number of `options`, returned in the order given. Options
+    /// must not contain commas.
+    pub fn choose_many<S: Into<String>>(
+        &mut self,
+        prompt: impl Into<String>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<String>, TestFailure> {
+        self.prompt(prompt).choose_many(options)
+    }
+
+    pub async fn choose_many_async<S: Into<String>>(
+        &mut self,
+        prompt: impl Into<String>,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<String>, TestFailure> {
+        self.prompt(prompt).choose_many_async(options).await
+    }
+
+    /// Waits for the operator to acknowledge, e.g. "Press Enter to continue"
+    pub fn acknowledge(&mut self, prompt: impl Into<String>) -> Result<(), TestFailure> {
+        self.prompt(prompt).acknowledge()
+    }
+
+    pub async fn acknowledge_async(
+        &mut self,
+        prompt: impl Into<String>,
+    ) -> Result<(), TestFailure> {
+        self.prompt(prompt).acknowledge_async().await
+    }
+
+    /// Asks the operator to pass or fail something only they can check, a
+    /// fail is returned as [`TestFailure::OperatorRejected`] with the
+    /// operator's comment
+    pub fn pass_fail(&mut self, prompt: impl Into<String>) -> Result<(), TestFailure> {
+        self.prompt(prompt).pass_fail()
+    }
+
+    pub async fn pass_fail_async(&mut self, prompt: impl Into<String>) -> Result<(), TestFailure> {
+        self.prompt(prompt).pass_fail_async().await
+    }
+
+    async fn ask_async(&mut self, prompt: Prompt) -> Result<String, TestFailure> {
+        // An abandoned test mustn't take the operator's answers from the
+        // tests that run after it
+        if self.test.is_cancelled() {
+            return Err(TestFailure::PromptUnanswered(prompt.text));
+        }
+
+        let input_rx = self.send_prompt(prompt.clone());
+
+        self.test
+            .write(Self::set_waiting_state(prompt.instructions.clone()));
+
+        let (input, timed_out) = self.wait_for_answer(&prompt, input_rx).await;
+
+        self.test
+            .write(Self::record_input(prompt.text, &input, timed_out));
+
+        input.map(|answer| answer.text)
+    }
+
+    /// Returns the answer and whether the prompt timed out
+    async fn wait_for_answer(
+        &self,
+        prompt: &Prompt,
+        mut input_rx: oneshot::Receiver<Result<Answer, TestFailure>>,
+    ) -> (Result<Answer, TestFailure>, bool) {
+        let Some(timeout) = prompt.timeout else {
+            return (
+                input_rx.await.unwrap_or(Err(TestFailure::SystemExited)),
+                false,
+            );
+        };
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        // Keeps the countdown in the TUI moving
+        let mut redraw = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            tokio::select! {
+                input = &mut input_rx => {
+                    return (input.unwrap_or(Err(TestFailure::SystemExited)), false);
+                }
+                _ = tokio::time::sleep_until(deadline) => break,
+                _ = redraw.tick() => {
+                    let _ = self.event_tx.send(Event::Nop);
+                }
+            }
+        }
+
+        // The TUI takes the prompt down once the channel is closed
+        drop(input_rx);
+        let _ = self.event_tx.send(Event::Nop);
+
+        let timed_out = TestFailure::OperatorTimeout {
+            prompt: prompt.text.clone(),
+            timeout,
+        };
+        // The default was checked when the prompt was built
+        let input = match &prompt.default {
+            Some(default) => Ok(Answer {
+                text: default.clone(),
+                scripted: false,
+            }),
+            None => Err(timed_out),
+        };
+        (input, true)
+    }
+
+    fn send_prompt(&self, prompt: Prompt) -> oneshot::Receiver<Result<Answer, TestFailure>> {
+        let (input_tx, input_rx) = oneshot::channel::<Result<Answer, TestFailure>>();
 
         self.event_tx
-            .send(Event::UserInputPrompt(prompt.into(), Some(input_tx)))
+            .send(Event::OperatorPrompt(prompt, Some(input_tx)))
             .expect("Failed to send user Prompt");
 
-        self.suites_data
-            .blocking_write(|f| {
-                f.current_suite_mut().current_test_mut().state =
-                    TestState::Running(TestRunning::WaitingForInput);
-                Ok(())
-            })
-            .expect("Failed to Write");
-
-        let input = input_rx
-            .blocking_recv()
-            .or(Err(TestFailure::SystemExited))?;
-
-        self.suites_data
-            .blocking_write(|f| {
-                f.current_suite_mut().current_test_mut().state =
-                    TestState::Running(TestRunning::Running);
-                Ok(())
-            })
-            .expect("Failed to write");
-
-        Ok(input)
+        input_rx
+    }
+
+    fn record_input(
+        prompt: String,
+        input: &Result<Answer, TestFailure>,
+        timed_out: bool,
+    ) -> impl FnOnce(&mut TestData, &mut Option<Instructions>) {
+        let response = input.as_ref().ok().map(|answer| answer.text.clone());
+        let scripted = input.as_ref().is_ok_and(|answer| answer.scripted);
+        move |test, shown| {
+            test.operator_inputs.push(OperatorInput {
+                prompt,
+                response,
+                timed_out,
+                scripted,
+            });
+            test.state = TestState::Running(TestRunning::Running);
+            *shown = None;
+        }
+    }
+
+    fn set_waiting_state(
+        instructions: Option<Instructions>,
+    ) -> impl FnOnce(&mut TestData, &mut Option<Instructions>) {
+        move |test, shown| {
+            test.state = TestState::Running(TestRunning::WaitingForInput);
+            *shown = instructions;
+        }
+    }
+}
+
+/// An operator prompt being built, see [`TextInput::prompt`]
+///
+/// Each way of asking comes as a pair: the `_async` method for async tests
+/// and a blocking one for sync tests, which waits for the `_async` one.
+pub struct PromptBuilder<'a> {
+    input: &'a mut TextInput,
+    text: String,
+    id: Option<String>,
+    timeout: Option<Duration>,
+    default: Option<String>,
+    instructions: Option<Instructions>,
+}
+
+impl<'a> PromptBuilder<'a> {
+    /// Names the prompt in response scripts, so the script doesn't break when
+    /// the prompt's text changes
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Stops waiting for the operator after `timeout`, the prompt then fails
+    /// with [`TestFailure::OperatorTimeout`] unless it has a default answer
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The answer used when the prompt times out. It's checked like any other
+    /// answer when the prompt is asked, and an invalid one fails the test
+    /// before the prompt is shown.
+    pub fn default_answer(mut self, answer: impl Into<String>) -> Self {
+        self.default = Some(answer.into());
+        self
+    }
+
+    /// Shows `instructions` in their own pane while the prompt waits
+    pub fn instructions(mut self, instructions: Instructions) -> Self {
+        self.instructions = Some(instructions);
+        self
+    }
+
+    /// Fails with [`TestFailure::InvalidDefaultAnswer`] before anything is
+    /// shown if the default isn't a valid answer to the prompt
+    fn into_prompt(self, kind: PromptKind) -> Result<(&'a mut TextInput, Prompt), TestFailure> {
+        let mut prompt = Prompt::new(self.text, kind);
+        prompt.id = self.id;
+        prompt.timeout = self.timeout;
+        prompt.instructions = self.instructions;
+        prompt.default = match self.default {
+            Some(default) => Some(prompt.check(&default).map_err(|reason| {
+                TestFailure::InvalidDefaultAnswer {
+                    prompt: prompt.text.clone(),
+                    answer: default,
+                    reason,
+                }
+            })?),
+            None => None,
+        };
+        Ok((self.input, prompt))
+    }
+
+    pub fn request(self) -> Result<String, TestFailure> {
+        wait(self.request_async())
+    }
+
+    pub async fn request_async(self) -> Result<String, TestFailure> {
+        let (input, prompt) = self.into_prompt(PromptKind::Text)?;
+        input.ask_async(prompt).await
+    }
+
+    pub fn confirm(self) -> Result<bool, TestFailure> {
+        wait(self.confirm_async())
+    }
+
+    pub async fn confirm_async(self) -> Result<bool, TestFailure> {
+        let (input, prompt) = self.into_prompt(PromptKind::Confirm)?;
+        Ok(input.ask_async(prompt).await? == "yes")
+    }
+
+    pub fn number(self, range: impl RangeBounds<f64>) -> Result<f64, TestFailure> {
+        wait(self.number_async(range))
+    }
+
+    pub async fn number_async(self, range: impl RangeBounds<f64>) -> Result<f64, TestFailure> {
+        let (input, prompt) = self.into_prompt(number_kind(range))?;
+        let answer = input.ask_async(prompt.clone()).await?;
+        parse_number(&prompt, &answer)
+    }
+
+    pub fn choose<S: Into<String>>(
+        self,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<String, TestFailure> {
+        wait(self.choose_async(options))
+    }
+
+    pub async fn choose_async<S: Into<String>>(
+        self,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<String, TestFailure> {
+        let options = options.into_iter().map(Into::into).collect();
+        let (input, prompt) = self.into_prompt(PromptKind::Choice(options))?;
+        input.ask_async(prompt).await
+    }
+
+    pub fn choose_many<S: Into<String>>(
+        self,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<String>, TestFailure> {
+        wait(self.choose_many_async(options))
+    }
+
+    pub async fn choose_many_async<S: Into<String>>(
+        self,
+        options: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<String>, TestFailure> {
+        let options = options.into_iter().map(Into::into).collect();
+        let (input, prompt) = self.into_prompt(PromptKind::MultiChoice(options))?;
+        Ok(split_choices(&input.ask_async(prompt).await?))
+    }
+
+    pub fn acknowledge(self) -> Result<(), TestFailure> {
+        wait(self.acknowledge_async())
+    }
+
+    pub async fn acknowledge_async(self) -> Result<(), TestFailure> {
+        let (input, prompt) = self.into_prompt(PromptKind::Acknowledge)?;
+        input.ask_async(prompt).await.map(drop)
+    }
+
+    pub fn pass_fail(self) -> Result<(), TestFailure> {
+        wait(self.pass_fail_async())
+    }
+
+    pub async fn pass_fail_async(self) -> Result<(), TestFailure> {
+        let (input, prompt) = self.into_prompt(PromptKind::PassFail)?;
+        let answer = input.ask_async(prompt.clone()).await?;
+        verdict(&prompt, &answer)
+    }
+}
+
+fn number_kind(range: impl RangeBounds<f64>) -> PromptKind {
+    PromptKind::Number {
+        min: range.start_bound().cloned(),
+        max: range.end_bound().cloned(),
+    }
+}
+
+/// Answers have already been checked, this only fails if a prompt responder
+/// skipped the check
+fn parse_number(prompt: &Prompt, answer: &str) -> Result<f64, TestFailure> {
+    answer
+        .parse()
+        .or(Err(TestFailure::PromptUnanswered(prompt.text.clone())))
+}
+
+/// Turns a checked pass/fail answer into the test's result
+fn verdict(prompt: &Prompt, answer: &str) -> Result<(), TestFailure> {
+    let (verdict, comment) = match answer.split_once(": ") {
+        Some((verdict, comment)) => (verdict, Some(comment.to_string())),
+        None => (answer, None),
+    };
+
+    match verdict {
+        "pass" => Ok(()),
+        _ => Err(TestFailure::OperatorRejected {
+            prompt: prompt.text.clone(),
+            comment,
+        }),
+    }
+}
+
+fn split_choices(answer: &str) -> Vec<String> {
+    answer
+        .split(", ")
+        .filter(|choice| !choice.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        record::StationInfo,
+        test_runner::data::suite::{SuiteData, SuiteDataCollection},
+    };
+
+    fn text_input() -> (TextInput, UnboundedReceiver<Event>) {
+        let (event_tx, event_rx) = unbounded_channel();
+        let suites = vec![SuiteData::new(vec![("test", &[])], "Suite", 0)];
+        let data = SuiteDataCollection::new(suites, StationInfo::default(), None, event_tx.clone());
+        (
+            TextInput::new(event_tx, TestHandle::new(data, 0, 0)),
+            event_rx,
+        )
+    }
+
+    #[tokio::test]
+    async fn invalid_default_fails_before_prompting() {
+        let (mut input, mut events) = text_input();
+
+        let failure = input
+            .prompt("Lid closed?")
+            .timeout(Duration::from_secs(30))
+            .default_answer("maybe")
+            .confirm_async()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            failure,
+            TestFailure::InvalidDefaultAnswer { ref answer, .. } if answer == "maybe"
+        ));
+        assert!(events.try_recv().is_err(), "the prompt was shown");
+    }
+
+    fn rejected(answer: &str) -> Option<String> {
+        let prompt = Prompt::new("LED lit?", PromptKind::PassFail);
+        match verdict(&prompt, answer) {
+            Err(TestFailure::OperatorRejected { prompt, comment }) => {
+                assert_eq!(prompt, "LED lit?");
+                comment
+            }
+            other => panic!("{answer:?} gave {other:?}"),
+        }
+    }
+
+    #[test]
+    fn pass_verdict_is_ok() {
+        let prompt = Prompt::new("LED lit?", PromptKind::PassFail);
+        assert!(verdict(&prompt, "pass").is_ok());
+        assert!(verdict(&prompt, "pass: a bit dim").is_ok());
+    }
+
+    #[test]
+    fn fail_verdict_keeps_the_comment() {
+        assert_eq!(rejected("fail"), None);
+        assert_eq!(rejected("fail: LED is dim"), Some("LED is dim".into()));
+        assert_eq!(rejected("fail: dim: and red"), Some("dim: and red".into()));
     }
 }
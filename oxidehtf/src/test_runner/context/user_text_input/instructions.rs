@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+/// What the operator has to do before answering a prompt, shown in its own
+/// pane while the prompt waits, e.g.
+/// `Instructions::new("Probe TP3").text("Hold the probe on **TP3**").image("tp3.png")`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Instructions {
+    pub title: String,
+    /// Paragraphs separated by blank lines. Lines starting with `#` are
+    /// headings and with `- ` are bullets, `**bold**` and `` `code` `` are
+    /// styled.
+    pub text: String,
+    /// Shown as is, or in place of the image where the terminal can't show
+    /// images
+    pub ascii_art: Option<String>,
+    /// A picture shown with the terminal's image protocol. PNGs work in
+    /// kitty, any format iTerm2 can open works in iTerm2 and WezTerm.
+    pub image: Option<PathBuf>,
+}
+
+impl Instructions {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.text = text.into();
+        self
+    }
+
+    pub fn ascii_art(mut self, art: impl Into<String>) -> Self {
+        self.ascii_art = Some(art.into());
+        self
+    }
+
+    pub fn image(mut self, path: impl Into<PathBuf>) -> Self {
+        self.image = Some(path.into());
+        self
+    }
+
+    /// The art to show where images can't be, if any
+    pub fn fallback(&self) -> Option<String> {
+        self.ascii_art.clone().or_else(|| {
+            self.image
+                .as_ref()
+                .map(|path| format!("[image: {}]", path.display()))
+        })
+    }
+}
@@ -0,0 +1,366 @@
+use std::{
+    ops::{Bound, RangeBounds},
+    time::Duration,
+};
+
+use tokio::sync::oneshot;
+
+use crate::TestFailure;
+
+use super::instructions::Instructions;
+
+/// Where the answer to a prompt is sent, dropped without an answer if the
+/// prompt times out
+pub type AnswerSender = oneshot::Sender<Result<Answer, TestFailure>>;
+
+/// What the operator is asked for, answers are checked against it before
+/// they're sent back to the test
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptKind {
+    /// Any text
+    Text,
+    /// `y`/`yes` or `n`/`no`, answered as `yes` or `no`
+    Confirm,
+    Number {
+        min: Bound<f64>,
+        max: Bound<f64>,
+    },
+    /// One of the options, given by name or by its number counting from 1
+    Choice(Vec<String>),
+    /// Any of the options separated by commas, answered in the order of the
+    /// options
+    MultiChoice(Vec<String>),
+    /// Anything, answered as `ok`
+    Acknowledge,
+    /// `pass` or `fail`, optionally followed by `: ` and a comment
+    PassFail,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prompt {
+    pub text: String,
+    /// Names the prompt in response scripts, see [`super::PromptBuilder::id`]
+    pub id: Option<String>,
+    pub kind: PromptKind,
+    /// How long to wait for the operator, see [`super::PromptBuilder::timeout`]
+    pub timeout: Option<Duration>,
+    /// The answer used when the prompt times out
+    pub default: Option<String>,
+    pub instructions: Option<Instructions>,
+}
+
+impl Prompt {
+    pub fn new(text: impl Into<String>, kind: PromptKind) -> Self {
+        Self {
+            text: text.into(),
+            id: None,
+            kind,
+            timeout: None,
+            default: None,
+            instructions: None,
+        }
+    }
+
+    /// The prompt as shown to the operator, with a hint of what's expected
+    pub fn title(&self) -> String {
+        let numbered = |options: &[String]| {
+            let options: Vec<String> = options
+                .iter()
+                .enumerate()
+                .map(|(i, option)| format!("{}: {}", i + 1, option))
+                .collect();
+            options.join(", ")
+        };
+
+        match &self.kind {
+            PromptKind::Text => self.text.clone(),
+            PromptKind::Confirm => format!("{} [y/n]", self.text),
+            PromptKind::Number { min, max } => match range_hint(*min, *max) {
+                Some(range) => format!("{} [number in {}]", self.text, range),
+                None => format!("{} [number]", self.text),
+            },
+            PromptKind::Choice(options) => format!("{} [{}]", self.text, numbered(options)),
+            PromptKind::MultiChoice(options) => {
+                format!("{} [any of {}]", self.text, numbered(options))
+            }
+            PromptKind::Acknowledge => format!("{} [press Enter]", self.text),
+            PromptKind::PassFail => format!("{} [pass/fail]", self.text),
+        }
+    }
+
+    /// Returns the answer in a normal form, or why it's not valid
+    pub fn check(&self, input: &str) -> Result<String, String> {
+        let trimmed = input.trim();
+
+        match &self.kind {
+            PromptKind::Text => Ok(input.to_string()),
+            PromptKind::Confirm => match trimmed.to_lowercase().as_str() {
+                "y" | "yes" => Ok("yes".into()),
+                "n" | "no" => Ok("no".into()),
+                _ => Err("Answer y or n".into()),
+            },
+            PromptKind::Number { min, max } => {
+                let value: f64 = trimmed
+                    .parse()
+                    .ok()
+                    .filter(|v: &f64| v.is_finite())
+                    .ok_or_else(|| format!("'{}' is not a number", trimmed))?;
+                if !(*min, *max).contains(&value) {
+                    return Err(format!(
+                        "{} is not in {}",
+                        value,
+                        range_hint(*min, *max).unwrap_or_default()
+                    ));
+                }
+                Ok(value.to_string())
+            }
+            PromptKind::Choice(options) => choose(options, trimmed).map(str::to_string),
+            PromptKind::MultiChoice(options) => {
+                let mut chosen = Vec::new();
+                for answer in trimmed.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+                    chosen.push(choose(options, answer)?);
+                }
+                let chosen: Vec<&str> = options
+                    .iter()
+                    .map(String::as_str)
+                    .filter(|option| chosen.contains(option))
+                    .collect();
+                Ok(chosen.join(", "))
+            }
+            PromptKind::Acknowledge => Ok("ok".into()),
+            PromptKind::PassFail => {
+                let (verdict, comment) = match trimmed.split_once(':') {
+                    Some((verdict, comment)) => (verdict.trim(), comment.trim()),
+                    None => (trimmed, ""),
+                };
+                let verdict = match verdict.to_lowercase().as_str() {
+                    "p" | "pass" => "pass",
+                    "f" | "fail" => "fail",
+                    _ => return Err("Answer pass or fail, then ': ' and a comment".into()),
+                };
+                match comment {
+                    "" => Ok(verdict.into()),
+                    comment => Ok(format!("{}: {}", verdict, comment)),
+                }
+            }
+        }
+    }
+}
+
+/// A checked answer to a prompt
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answer {
+    pub text: String,
+    /// Given by a response script rather than the operator
+    pub scripted: bool,
+}
+
+impl std::fmt::Display for Prompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+fn choose<'a>(options: &'a [String], answer: &str) -> Result<&'a str, String> {
+    let by_number = answer
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| options.get(n.checked_sub(1)?));
+    let by_name = || {
+        options
+            .iter()
+            .find(|option| option.eq_ignore_ascii_case(answer))
+    };
+
+    by_number
+        .or_else(by_name)
+        .map(String::as_str)
+        .ok_or_else(|| format!("'{}' is not one of the options", answer))
+}
+
+/// Interval notation such as `[0, 5)`, `None` if unbounded on both ends
+fn range_hint(min: Bound<f64>, max: Bound<f64>) -> Option<String> {
+    let low = match min {
+        Bound::Included(v) => format!("[{}", v),
+        Bound::Excluded(v) => format!("({}", v),
+        Bound::Unbounded => "(-∞".into(),
+    };
+    let high = match max {
+        Bound::Included(v) => format!("{}]", v),
+        Bound::Excluded(v) => format!("{})", v),
+        Bound::Unbounded => "∞)".into(),
+    };
+
+    match (min, max) {
+        (Bound::Unbounded, Bound::Unbounded) => None,
+        _ => Some(format!("{}, {}", low, high)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(options: &[&str]) -> Vec<String> {
+        options.iter().map(|o| o.to_string()).collect()
+    }
+
+    fn check(kind: PromptKind, input: &str) -> Result<String, String> {
+        Prompt::new("Prompt", kind).check(input)
+    }
+
+    #[test]
+    fn text_is_kept_as_typed() {
+        assert_eq!(check(PromptKind::Text, " SN-0001 "), Ok(" SN-0001 ".into()));
+        assert_eq!(check(PromptKind::Text, ""), Ok("".into()));
+    }
+
+    #[test]
+    fn confirm() {
+        for (input, answer) in [("y", "yes"), (" YES ", "yes"), ("n", "no"), ("No", "no")] {
+            assert_eq!(check(PromptKind::Confirm, input), Ok(answer.into()));
+        }
+        assert_eq!(
+            check(PromptKind::Confirm, "maybe"),
+            Err("Answer y or n".into())
+        );
+        assert_eq!(check(PromptKind::Confirm, ""), Err("Answer y or n".into()));
+    }
+
+    #[test]
+    fn number() {
+        let kind = PromptKind::Number {
+            min: Bound::Included(0.0),
+            max: Bound::Excluded(5.0),
+        };
+        assert_eq!(check(kind.clone(), " 0 "), Ok("0".into()));
+        assert_eq!(check(kind.clone(), "3.30"), Ok("3.3".into()));
+        assert_eq!(check(kind.clone(), "5"), Err("5 is not in [0, 5)".into()));
+        assert_eq!(check(kind.clone(), "-1"), Err("-1 is not in [0, 5)".into()));
+        assert_eq!(
+            check(kind.clone(), "three"),
+            Err("'three' is not a number".into())
+        );
+        assert_eq!(check(kind, "NaN"), Err("'NaN' is not a number".into()));
+
+        let any = PromptKind::Number {
+            min: Bound::Unbounded,
+            max: Bound::Unbounded,
+        };
+        assert_eq!(check(any.clone(), "-1e3"), Ok("-1000".into()));
+        assert_eq!(check(any, "inf"), Err("'inf' is not a number".into()));
+    }
+
+    #[test]
+    fn choice() {
+        let kind = PromptKind::Choice(options(&["Red", "Green"]));
+        assert_eq!(check(kind.clone(), "green"), Ok("Green".into()));
+        assert_eq!(check(kind.clone(), " 1 "), Ok("Red".into()));
+        assert_eq!(
+            check(kind.clone(), "3"),
+            Err("'3' is not one of the options".into())
+        );
+        assert_eq!(
+            check(kind.clone(), "0"),
+            Err("'0' is not one of the options".into())
+        );
+        assert_eq!(
+            check(kind, "Blue"),
+            Err("'Blue' is not one of the options".into())
+        );
+    }
+
+    #[test]
+    fn multi_choice() {
+        let kind = PromptKind::MultiChoice(options(&["USB", "UART", "SPI"]));
+        assert_eq!(check(kind.clone(), "spi, 1"), Ok("USB, SPI".into()));
+        assert_eq!(check(kind.clone(), "uart,,uart"), Ok("UART".into()));
+        assert_eq!(check(kind.clone(), ""), Ok("".into()));
+        assert_eq!(
+            check(kind, "USB, I2C"),
+            Err("'I2C' is not one of the options".into())
+        );
+    }
+
+    #[test]
+    fn acknowledge_takes_anything() {
+        assert_eq!(check(PromptKind::Acknowledge, ""), Ok("ok".into()));
+        assert_eq!(check(PromptKind::Acknowledge, "done"), Ok("ok".into()));
+    }
+
+    #[test]
+    fn pass_fail() {
+        assert_eq!(check(PromptKind::PassFail, "P"), Ok("pass".into()));
+        assert_eq!(check(PromptKind::PassFail, " FAIL "), Ok("fail".into()));
+        assert_eq!(
+            check(PromptKind::PassFail, "f:  LED is dim "),
+            Ok("fail: LED is dim".into())
+        );
+        assert_eq!(check(PromptKind::PassFail, "pass:"), Ok("pass".into()));
+        assert_eq!(
+            check(PromptKind::PassFail, "ok"),
+            Err("Answer pass or fail, then ': ' and a comment".into())
+        );
+    }
+
+    #[test]
+    fn range_hints() {
+        use Bound::*;
+
+        assert_eq!(
+            range_hint(Included(0.0), Excluded(5.0)),
+            Some("[0, 5)".into())
+        );
+        assert_eq!(
+            range_hint(Excluded(-1.5), Included(2.0)),
+            Some("(-1.5, 2]".into())
+        );
+        assert_eq!(range_hint(Unbounded, Included(3.0)), Some("(-∞, 3]".into()));
+        assert_eq!(range_hint(Included(3.0), Unbounded), Some("[3, ∞)".into()));
+        assert_eq!(range_hint(Unbounded, Unbounded), None);
+    }
+
+    #[test]
+    fn choose_by_number_or_name() {
+        let options = options(&["Red", "Green", "2"]);
+        assert_eq!(choose(&options, "2"), Ok("Green"));
+        assert_eq!(choose(&options, "RED"), Ok("Red"));
+        assert_eq!(choose(&options, "3"), Ok("2"));
+        assert_eq!(
+            choose(&options, "4"),
+            Err("'4' is not one of the options".into())
+        );
+    }
+
+    #[test]
+    fn titles_hint_at_the_answer() {
+        let title = |kind| Prompt::new("Rail?", kind).title();
+
+        assert_eq!(title(PromptKind::Text), "Rail?");
+        assert_eq!(title(PromptKind::Confirm), "Rail? [y/n]");
+        assert_eq!(
+            title(PromptKind::Number {
+                min: Bound::Included(0.0),
+                max: Bound::Included(5.0),
+            }),
+            "Rail? [number in [0, 5]]"
+        );
+        assert_eq!(
+            title(PromptKind::Number {
+                min: Bound::Unbounded,
+                max: Bound::Unbounded,
+            }),
+            "Rail? [number]"
+        );
+        assert_eq!(
+            title(PromptKind::Choice(options(&["1V8", "3V3"]))),
+            "Rail? [1: 1V8, 2: 3V3]"
+        );
+        assert_eq!(
+            title(PromptKind::MultiChoice(options(&["1V8", "3V3"]))),
+            "Rail? [any of 1: 1V8, 2: 3V3]"
+        );
+        assert_eq!(title(PromptKind::Acknowledge), "Rail? [press Enter]");
+        assert_eq!(title(PromptKind::PassFail), "Rail? [pass/fail]");
+    }
+}
@@ -5,7 +5,7 @@ use indexmap::IndexMap;
 pub mod suite;
 
 // use crate::common::*;
-use crate::test_runner::MeasurementDefinition;
+use crate::test_runner::{context::measurement::MeasurementStatus, MeasurementDefinition};
 
 use super::TestFailure;
 
@@ -28,7 +28,17 @@ pub enum TestRunning {
 pub enum TestDone {
     #[default]
     Passed,
+    /// Passed with at least one measurement outside its marginal limits
+    PassedMarginal,
     Failed(TestFailure),
+    Errored(TestFailure),
+    Skipped(String),
+}
+
+impl TestDone {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Failed(_) | Self::Errored(_))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,4 +47,45 @@ pub struct TestData {
     pub duration: Duration,
     pub state: TestState,
     pub user_data: IndexMap<String, MeasurementDefinition>,
+    pub operator_inputs: Vec<OperatorInput>,
+}
+
+impl TestData {
+    pub fn has_marginal_measurement(&self) -> bool {
+        self.user_data
+            .values()
+            .any(|def| def.status == MeasurementStatus::Marginal)
+    }
+
+    /// Adds any of `declared` that haven't been set as unset measurements and
+    /// returns the failure for the first of them
+    pub fn check_declared(&mut self, declared: &[String]) -> Option<TestFailure> {
+        let mut unset = None;
+
+        for name in declared {
+            let def = self.user_data.entry(name.clone()).or_default();
+            if def.status == MeasurementStatus::Unset && unset.is_none() {
+                unset = Some(TestFailure::MeasurementNotSet(name.clone()));
+            }
+        }
+
+        unset
+    }
+
+    /// The first recorded failure among the test's measurements
+    pub fn measurement_failure(&self) -> Option<TestFailure> {
+        self.user_data.values().find_map(|def| def.failure.clone())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct OperatorInput {
+    pub prompt: String,
+    /// `None` if the prompt was not answered
+    pub response: Option<String>,
+    /// The operator didn't answer in time, `response` is the default answer
+    /// if there was one
+    pub timed_out: bool,
+    /// Answered by a response script, see [`crate::RunConfig::response_script`]
+    pub scripted: bool,
 }
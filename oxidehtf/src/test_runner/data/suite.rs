@@ -1,15 +1,31 @@
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        PoisonError,
+    },
+    time::Duration,
+};
 
 use super::{TestData, TestState};
-use crate::{common::*, test_runner::MeasurementDefinition};
+use crate::{
+    common::*,
+    record::{LimitsFileRecord, StationInfo, TestOutcome},
+    test_runner::{
+        context::user_text_input::instructions::Instructions, MeasurementDefinition, TestFailure,
+    },
+};
 use chrono::{DateTime, FixedOffset, Utc};
-use indexmap::IndexMap;
 
 #[derive(Debug, Clone)]
 pub struct SuiteDataCollectionRaw {
     pub inner: Vec<SuiteData>,
     pub dut_id: String,
+    pub station: StationInfo,
+    pub limits_file: Option<LimitsFileRecord>,
+    pub start_time: DateTime<FixedOffset>,
     pub current: usize,
+    /// Shown to the operator while a prompt waits
+    pub instructions: Option<Instructions>,
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +34,17 @@ pub struct SuiteDataCollection {
     pub event_tx: UnboundedSender<Event>,
 }
 
+/// The test a [`crate::SysContext`] writes to. A sync test abandoned after a
+/// timeout keeps running on its thread, once it's cancelled its writes are
+/// dropped rather than landing on a test that already finished.
+#[derive(Debug, Clone)]
+pub struct TestHandle {
+    data: SuiteDataCollection,
+    suite: usize,
+    test: usize,
+    cancelled: Arc<AtomicBool>,
+}
+
 #[derive(Debug, Clone)]
 pub struct SuiteData {
     pub name: &'static str,
@@ -25,14 +52,24 @@ pub struct SuiteData {
     pub start_time: DateTime<FixedOffset>,
     pub test_data: Vec<TestData>,
     pub current_index: usize,
+    pub lifecycle_errors: Vec<TestFailure>,
 }
 
 impl SuiteDataCollection {
-    pub fn new(suites_data: Vec<SuiteData>, event_tx: UnboundedSender<Event>) -> Self {
+    pub fn new(
+        suites_data: Vec<SuiteData>,
+        station: StationInfo,
+        limits_file: Option<LimitsFileRecord>,
+        event_tx: UnboundedSender<Event>,
+    ) -> Self {
         let collection_holder = Arc::new(RwLock::new(SuiteDataCollectionRaw {
             inner: suites_data,
             dut_id: String::new(),
+            station,
+            limits_file,
+            start_time: Default::default(),
             current: 0,
+            instructions: None,
         }));
 
         Self {
@@ -41,62 +78,98 @@ impl SuiteDataCollection {
         }
     }
 
-    pub fn blocking_write<F, R>(&self, f: F) -> Result<R>
+    /// Changes the data and tells the UI. The lock is only held while `f`
+    /// runs and is never held across an `.await`, so this is called the same
+    /// way from sync and async code.
+    pub fn write<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&mut SuiteDataCollectionRaw) -> Result<R>,
     {
-        let mut data_guard = self.data.blocking_write();
+        let mut data_guard = self.data.write().unwrap_or_else(PoisonError::into_inner);
         let result = f(&mut data_guard);
         drop(data_guard);
         self.event_tx.send(Event::UpdatedTestData)?;
         result
     }
 
-    pub async fn write<F, R>(&self, f: F) -> Result<R>
-    where
-        F: FnOnce(&mut SuiteDataCollectionRaw) -> Result<R>,
-    {
-        let mut data_guard = self.data.write().await;
-        let result = f(&mut data_guard);
-        drop(data_guard);
-        self.event_tx.send(Event::UpdatedTestData)?;
-        result
-    }
-
-    pub fn blocking_read<F, R>(&self, f: F) -> Result<R>
+    pub fn read<F, R>(&self, f: F) -> Result<R>
     where
         F: FnOnce(&SuiteDataCollectionRaw) -> Result<R>,
     {
-        let mut data_guard = self.data.blocking_read();
-        let result = f(&mut data_guard);
-        drop(data_guard);
-        result
+        let data_guard = self.data.read().unwrap_or_else(PoisonError::into_inner);
+        f(&data_guard)
     }
 
     pub fn set_dut_id(&self, id: impl Into<String>) {
-        self.blocking_write(|d| {
+        self.write(|d| {
             d.dut_id = id.into();
             Ok(())
         })
         .unwrap();
     }
 
-    pub fn blocking_get_raw_copy(&self) -> SuiteDataCollectionRaw {
-        self.blocking_read(|d| Ok(d.clone())).unwrap()
+    pub fn get_raw_copy(&self) -> SuiteDataCollectionRaw {
+        self.read(|d| Ok(d.clone())).unwrap()
+    }
+}
+
+impl TestHandle {
+    pub fn new(data: SuiteDataCollection, suite: usize, test: usize) -> Self {
+        Self {
+            data,
+            suite,
+            test,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
     }
 
-    pub async fn get_raw_copy(&self) -> SuiteDataCollectionRaw {
-        self.data.read().await.clone()
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Changes the test's data and the instructions pane, `None` if the test
+    /// was cancelled
+    pub fn write<F, R>(&self, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut TestData, &mut Option<Instructions>) -> R,
+    {
+        let written = self.data.write(|d| {
+            let test = &mut d.inner[self.suite].test_data[self.test];
+            // Checked under the lock, so nothing is written once the runner
+            // has moved on
+            if self.is_cancelled() {
+                warn!("Dropped a write from abandoned test '{}'", test.name);
+                return Ok(None);
+            }
+            Ok(Some(f(test, &mut d.instructions)))
+        });
+
+        written.ok().flatten()
     }
 }
 
 impl SuiteDataCollectionRaw {
+    pub fn set_run_start_time(&mut self) -> Result<()> {
+        let fixed_offset = FixedOffset::west_opt(0).unwrap();
+        self.start_time = Utc::now().with_timezone(&fixed_offset);
+        Ok(())
+    }
+
     pub fn set_suite_start_time(&mut self) -> Result<()> {
         let fixed_offset = FixedOffset::west_opt(0).unwrap();
         self.inner[self.current].start_time = Utc::now().with_timezone(&fixed_offset);
         Ok(())
     }
 
+    /// The overall outcome of the run so far
+    pub fn outcome(&self) -> TestOutcome {
+        TestOutcome::aggregate(self.inner.iter().map(SuiteData::outcome))
+    }
+
     pub fn get_current_suite_index(&self) -> usize {
         self.current
     }
@@ -117,24 +190,45 @@ impl SuiteDataCollectionRaw {
 }
 
 impl SuiteData {
-    pub fn new(func_names: Vec<&'static str>, suite_name: &'static str, priority: usize) -> Self {
+    /// `tests` pairs each test name with the measurements it declares
+    pub fn new(
+        tests: Vec<(&'static str, &'static [&'static str])>,
+        suite_name: &'static str,
+        priority: usize,
+    ) -> Self {
         Self {
             name: suite_name,
-            test_data: func_names
-                .iter()
-                .map(|n| TestData {
-                    name: *n,
+            test_data: tests
+                .into_iter()
+                .map(|(name, declared)| TestData {
+                    name,
                     state: TestState::InQueue,
-                    user_data: IndexMap::new(),
+                    user_data: declared
+                        .iter()
+                        .map(|m| (m.to_string(), MeasurementDefinition::default()))
+                        .collect(),
+                    operator_inputs: Vec::new(),
                     duration: Duration::default(),
                 })
                 .collect(),
             current_index: 0,
+            lifecycle_errors: Vec::new(),
             start_time: Default::default(),
             priority,
         }
     }
 
+    pub fn outcome(&self) -> TestOutcome {
+        let lifecycle = (!self.lifecycle_errors.is_empty()).then_some(TestOutcome::Errored);
+
+        TestOutcome::aggregate(
+            self.test_data
+                .iter()
+                .map(|test| TestOutcome::from(&test.state))
+                .chain(lifecycle),
+        )
+    }
+
     pub fn get_test_amount(&self) -> usize {
         self.test_data.len()
     }
@@ -168,8 +262,4 @@ impl SuiteData {
     pub fn get_test_name(&self) -> &'static str {
         self.current_test().name
     }
-
-    pub fn insert_measurement(&mut self, name: &str, def: MeasurementDefinition) {
-        self.current_test_mut().user_data.insert(name.into(), def);
-    }
 }
@@ -1,3 +1,7 @@
+use std::time::Duration;
+
+use super::LifecycleHook;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum TestFailure {
     AssertionFailed {
@@ -6,18 +10,70 @@ pub enum TestFailure {
         file: &'static str,
         line: u32,
     },
-    MeasurementNotInRange {
+    /// A measurement broke one or more of its rules, every rule is checked
+    /// so all of them are listed
+    MeasurementFailed {
         name: String,
-        expected: (f64, f64),
-        found: f64,
+        failures: Vec<RuleFailure>,
         file: &'static str,
         line: u32,
     },
     MeasurementDoesntExist(String),
+    /// A declared measurement was never set
+    MeasurementNotSet(String),
+    PromptUnanswered(String),
+    /// The operator didn't answer a prompt in time and it had no default
+    OperatorTimeout {
+        prompt: String,
+        timeout: Duration,
+    },
+    /// The operator failed a pass/fail prompt
+    OperatorRejected {
+        prompt: String,
+        comment: Option<String>,
+    },
+    /// A prompt's default answer isn't a valid answer to it
+    InvalidDefaultAnswer {
+        prompt: String,
+        answer: String,
+        reason: String,
+    },
+    /// A response script had no valid answer for a prompt
+    ScriptedAnswerFailed {
+        prompt: String,
+        reason: String,
+    },
+    Timeout {
+        limit: Duration,
+        elapsed: Duration,
+    },
+    LifecycleFailed {
+        hook: LifecycleHook,
+        message: String,
+    },
+    Panicked {
+        message: String,
+        location: Option<String>,
+        backtrace: String,
+    },
     SystemExited,
     SystemError,
 }
 
+/// A rule a measurement broke, see [`TestFailure::MeasurementFailed`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleFailure {
+    /// Description of the rule that failed
+    pub rule: String,
+    pub reason: String,
+}
+
+impl std::fmt::Display for RuleFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Expected: {} - {}", self.rule, self.reason)
+    }
+}
+
 impl std::fmt::Display for TestFailure {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -31,20 +87,99 @@ impl std::fmt::Display for TestFailure {
                 "Assertion Failed at {}:{} - Expected: {} - Found: {}",
                 file, line, expected, found
             ),
-            Self::MeasurementNotInRange {
+            Self::MeasurementFailed {
                 name,
-                expected,
-                found,
-                file: _,
-                line: _,
+                failures,
+                file,
+                line,
+            } => {
+                let failures: Vec<String> = failures.iter().map(|f| f.to_string()).collect();
+                write!(
+                    f,
+                    "Measurement '{}' at {}:{} - {}",
+                    name,
+                    file,
+                    line,
+                    failures.join("; ")
+                )
+            }
+            Self::MeasurementDoesntExist(name) => write!(f, "Measurement '{}' doesn't exist", name),
+            Self::MeasurementNotSet(name) => {
+                write!(f, "Measurement '{}' was declared but never set", name)
+            }
+            Self::Timeout { limit, elapsed } => {
+                write!(f, "Timed out after {:.2?} - Limit: {:.2?}", elapsed, limit)
+            }
+            Self::LifecycleFailed { hook, message } => write!(f, "{} failed - {}", hook, message),
+            Self::Panicked {
+                message,
+                location,
+                backtrace: _,
+            } => match location {
+                Some(location) => write!(f, "Panicked at {} - {}", location, message),
+                None => write!(f, "Panicked - {}", message),
+            },
+            Self::PromptUnanswered(prompt) => write!(f, "Prompt '{}' was not answered", prompt),
+            Self::OperatorTimeout { prompt, timeout } => write!(
+                f,
+                "Prompt '{}' was not answered within {:.2?}",
+                prompt, timeout
+            ),
+            Self::OperatorRejected { prompt, comment } => match comment {
+                Some(comment) => write!(f, "Operator rejected '{}' - {}", prompt, comment),
+                None => write!(f, "Operator rejected '{}'", prompt),
+            },
+            Self::InvalidDefaultAnswer {
+                prompt,
+                answer,
+                reason,
             } => write!(
                 f,
-                "Measurement '{}' - Expected: {}-{} - Found: {}",
-                name, expected.0, expected.1, found,
+                "Default answer '{}' to '{}' is not valid - {}",
+                answer, prompt, reason
             ),
-            Self::MeasurementDoesntExist(name) => write!(f, "Measurement '{}' doesn't exist", name),
+            Self::ScriptedAnswerFailed { prompt, reason } => {
+                write!(f, "Scripted answer to '{}' failed - {}", prompt, reason)
+            }
             Self::SystemExited => write!(f, "System Exited"),
             Self::SystemError => write!(f, "System Failed and Exited"),
         }
     }
 }
+
+impl TestFailure {
+    /// Where in the test code the failure happened, if known
+    pub fn location(&self) -> Option<(String, u32)> {
+        match self {
+            Self::AssertionFailed { file, line, .. }
+            | Self::MeasurementFailed { file, line, .. } => Some((file.to_string(), *line)),
+            Self::Panicked {
+                location: Some(location),
+                ..
+            } => {
+                let (file, line) = location.rsplit_once(':')?;
+                Some((file.to_string(), line.parse().ok()?))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::AssertionFailed { .. } => "AssertionFailed",
+            Self::MeasurementFailed { .. } => "MeasurementFailed",
+            Self::MeasurementDoesntExist(_) => "MeasurementDoesntExist",
+            Self::MeasurementNotSet(_) => "MeasurementNotSet",
+            Self::PromptUnanswered(_) => "PromptUnanswered",
+            Self::OperatorTimeout { .. } => "OperatorTimeout",
+            Self::OperatorRejected { .. } => "OperatorRejected",
+            Self::InvalidDefaultAnswer { .. } => "InvalidDefaultAnswer",
+            Self::ScriptedAnswerFailed { .. } => "ScriptedAnswerFailed",
+            Self::Timeout { .. } => "Timeout",
+            Self::LifecycleFailed { .. } => "LifecycleFailed",
+            Self::Panicked { .. } => "Panicked",
+            Self::SystemExited => "SystemExited",
+            Self::SystemError => "SystemError",
+        }
+    }
+}
@@ -1,4 +1,6 @@
-use std::any::Any;
+use std::{any::Any, time::Duration};
+
+use futures::future::BoxFuture;
 
 use crate::common::*;
 
@@ -7,9 +9,90 @@ use super::{SysContext, TestFailure, TestLifecycle};
 pub type DynTestFn =
     Box<dyn Fn(&mut dyn SuiteProducer, &mut SysContext) -> Result<(), TestFailure> + Send + Sync>;
 
+pub type DynAsyncTestFn = Box<
+    dyn for<'a> Fn(
+            &'a mut dyn SuiteProducer,
+            &'a mut SysContext,
+        ) -> BoxFuture<'a, Result<(), TestFailure>>
+        + Send
+        + Sync,
+>;
+
+pub enum TestFn {
+    Sync(DynTestFn),
+    Async(DynAsyncTestFn),
+}
+
+impl TestFn {
+    pub fn new_sync<F>(func: F) -> Self
+    where
+        F: Fn(&mut dyn SuiteProducer, &mut SysContext) -> Result<(), TestFailure>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::Sync(Box::new(func))
+    }
+
+    pub fn new_async<F>(func: F) -> Self
+    where
+        F: for<'a> Fn(
+                &'a mut dyn SuiteProducer,
+                &'a mut SysContext,
+            ) -> BoxFuture<'a, Result<(), TestFailure>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Self::Async(Box::new(func))
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct TestConfig {
+    /// Fails the test with [`TestFailure::Timeout`] if it runs longer than this,
+    /// overrides [`SuiteConfig::timeout`]
+    pub timeout: Option<Duration>,
+    /// Marks the test as skipped with this reason instead of running it
+    pub skip: Option<&'static str>,
+    /// Failing measurements don't end the test, it fails after returning
+    /// instead
+    pub non_fatal_measurements: bool,
+    /// Measurements the test must set, the test fails if any of them is
+    /// still unset once it returns
+    pub measurements: &'static [&'static str],
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SuiteConfig {
+    /// Default timeout for every test in the suite
+    pub timeout: Option<Duration>,
+    /// Overrides the run wide [`FailurePolicy`] for this suite
+    pub on_failure: Option<FailurePolicy>,
+    /// A blocking test that times out keeps the suite until it returns, so
+    /// by default the runner waits for it and then tears the suite down
+    /// before moving on. Set this to move on straight away instead, the
+    /// suite's teardown is then never run.
+    pub abandon_on_timeout: bool,
+}
+
+/// What the runner does once a test fails or errors. Tests that are not run
+/// because of it are marked as skipped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FailurePolicy {
+    #[default]
+    Continue,
+    StopSuite,
+    StopRun,
+}
+
 pub trait SuiteProducer: TestLifecycle + Send + Sync + Any {
-    fn get_tests(&self) -> Vec<(&'static str, DynTestFn)>;
+    fn get_tests(&self) -> Vec<(&'static str, TestFn, TestConfig)>;
     fn get_suite_name(&self) -> &'static str;
+
+    fn get_suite_config(&self) -> SuiteConfig {
+        SuiteConfig::default()
+    }
 }
 
 pub struct SuiteProducerGenerator {
@@ -1,5 +1,14 @@
+use async_trait::async_trait;
+
 use crate::common::*;
 
+/// Hooks run by the test runner around a suite and each of its tests.
+///
+/// The `_async` variants are what the runner calls; by default they run the
+/// synchronous hook with [`tokio::task::block_in_place`], which is why
+/// [`crate::run_tests_with`] always runs on a multi-threaded runtime. Override
+/// them with `#[oxidehtf::async_trait]` on the impl to use async code instead.
+#[async_trait]
 pub trait TestLifecycle: 'static + Send + Sync {
     fn setup(&mut self) -> Result<()> {
         Ok(())
@@ -16,4 +25,39 @@ pub trait TestLifecycle: 'static + Send + Sync {
     fn teardown(&mut self) -> Result<()> {
         Ok(())
     }
+
+    async fn setup_async(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| self.setup())
+    }
+
+    async fn before_test_async(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| self.before_test())
+    }
+
+    async fn after_test_async(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| self.after_test())
+    }
+
+    async fn teardown_async(&mut self) -> Result<()> {
+        tokio::task::block_in_place(|| self.teardown())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleHook {
+    Setup,
+    BeforeTest,
+    AfterTest,
+    Teardown,
+}
+
+impl std::fmt::Display for LifecycleHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Setup => write!(f, "Setup"),
+            Self::BeforeTest => write!(f, "Before Test"),
+            Self::AfterTest => write!(f, "After Test"),
+            Self::Teardown => write!(f, "Teardown"),
+        }
+    }
 }
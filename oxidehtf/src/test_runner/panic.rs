@@ -0,0 +1,96 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    cell::{Cell, RefCell},
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::Pin,
+    sync::Once,
+    task::{Context, Poll},
+};
+
+use crate::common::*;
+
+use super::TestFailure;
+
+struct PanicContext {
+    location: Option<String>,
+    backtrace: String,
+}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LAST_PANIC: RefCell<Option<PanicContext>> = const { RefCell::new(None) };
+}
+
+/// Installs a panic hook that records where a test panicked. Panics outside of
+/// [`catch_panic`] are passed on to the previous hook, so this must be called
+/// after the TUI has installed its own.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                previous(info);
+                return;
+            }
+
+            error!("Test panicked: {}", info);
+
+            LAST_PANIC.set(Some(PanicContext {
+                location: info
+                    .location()
+                    .map(|l| format!("{}:{}", l.file(), l.line())),
+                backtrace: Backtrace::force_capture().to_string(),
+            }));
+        }));
+    });
+}
+
+pub fn catch_panic<R>(f: impl FnOnce() -> R) -> Result<R, TestFailure> {
+    let was_catching = CATCHING.replace(true);
+    let result = std::panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(was_catching);
+
+    result.map_err(panic_failure)
+}
+
+pub fn catch_panic_async<F: Future + Unpin>(future: F) -> CatchPanic<F> {
+    CatchPanic { future }
+}
+
+pub struct CatchPanic<F> {
+    future: F,
+}
+
+impl<F: Future + Unpin> Future for CatchPanic<F> {
+    type Output = Result<F::Output, TestFailure>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match catch_panic(|| Pin::new(&mut self.future).poll(cx)) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(e) => Poll::Ready(Err(e)),
+        }
+    }
+}
+
+fn panic_failure(payload: Box<dyn Any + Send>) -> TestFailure {
+    let message = if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "Box<dyn Any>".to_string()
+    };
+
+    let context = LAST_PANIC.take();
+
+    TestFailure::Panicked {
+        message,
+        location: context.as_ref().and_then(|c| c.location.clone()),
+        backtrace: context.map(|c| c.backtrace).unwrap_or_default(),
+    }
+}
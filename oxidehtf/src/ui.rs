@@ -25,7 +25,6 @@ pub enum Screens {
 pub struct Ui {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     event_tx: UnboundedSender<Event>,
-    focus_stack: Vec<Id>,
     components: HashMap<Id, Box<dyn Component>>,
     screens: HashMap<Screens, Box<dyn Screen>>,
     current_focus: Option<Id>,
@@ -38,7 +37,6 @@ impl Ui {
             terminal: ratatui::init(),
             event_tx: event_tx.clone(),
 
-            focus_stack: Vec::new(),
             components: HashMap::new(),
             screens: HashMap::from([
                 (
@@ -68,10 +66,10 @@ impl Ui {
                 .unwrap();
         }
 
-        self.event_tx.send(Event::NOP).unwrap();
+        self.event_tx.send(Event::Nop).unwrap();
 
         let event_loop = Self::event_loop(self.event_tx.clone());
-        tokio::spawn(async { event_loop.await });
+        tokio::spawn(event_loop);
     }
 
     pub async fn event_loop(event_tx: UnboundedSender<Event>) {
@@ -172,17 +170,22 @@ impl Ui {
         self.components.get_mut(id).unwrap().set_attr(attr).unwrap();
     }
 
-    pub fn render(&mut self, mut data: SuiteDataCollectionRaw) -> Result<()> {
+    pub fn render(&mut self, data: SuiteDataCollectionRaw) -> Result<()> {
         let mut result = Ok(());
         self.terminal.draw(|f| {
             result = self.screens.get_mut(&self.current_screen).unwrap().draw(
                 f,
                 &mut self.components,
-                &mut data,
+                &data,
             )
         })?;
+        result?;
 
-        result
+        for component in self.components.values_mut() {
+            component.draw_graphics(self.terminal.backend_mut())?;
+        }
+
+        Ok(())
     }
 
     fn from_crossterm(crossterm_event: crossterm::event::Event) -> Option<Event> {
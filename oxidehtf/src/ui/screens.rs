@@ -1,8 +1,13 @@
 use std::collections::HashMap;
 
-use crate::{app::Id, common::*, test_runner::SuiteDataCollectionRaw};
+use crate::{
+    app::Id,
+    common::*,
+    record::TestOutcome,
+    test_runner::{SuiteDataCollectionRaw, TestDone},
+};
 use components::Component;
-use ratatui::Frame;
+use ratatui::{style::Style, Frame};
 
 pub mod components;
 pub mod running;
@@ -23,3 +28,23 @@ pub trait Screen {
         state: &SuiteDataCollectionRaw,
     ) -> Result<()>;
 }
+
+pub fn done_style(done: &TestDone) -> Style {
+    match done {
+        TestDone::Passed => Style::default().green(),
+        TestDone::PassedMarginal => Style::default().yellow(),
+        TestDone::Failed(_) => Style::default().red(),
+        TestDone::Errored(_) => Style::default().magenta(),
+        TestDone::Skipped(_) => Style::default().dark_gray(),
+    }
+}
+
+pub fn outcome_style(outcome: TestOutcome) -> Style {
+    match outcome {
+        TestOutcome::Passed => Style::default().green(),
+        TestOutcome::Marginal => Style::default().yellow(),
+        TestOutcome::Failed | TestOutcome::NotRun => Style::default().red(),
+        TestOutcome::Errored => Style::default().magenta(),
+        TestOutcome::Skipped => Style::default().dark_gray(),
+    }
+}
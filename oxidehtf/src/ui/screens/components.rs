@@ -43,4 +43,10 @@ pub trait Component {
 
     fn draw(&mut self, frame: &mut Frame, area: Rect, state: &SuiteDataCollectionRaw)
         -> Result<()>;
+
+    /// Writes to the terminal directly after the frame is drawn, for images
+    fn draw_graphics(&mut self, out: &mut dyn std::io::Write) -> Result<()> {
+        let _ = out;
+        Ok(())
+    }
 }
@@ -12,8 +12,9 @@ use super::Screen;
 use crate::{app::Id, common::*, test_runner::SuiteDataCollectionRaw};
 use completed_tests::CompletedTestDisplay;
 use current_test::CurrentTestDisplay;
+use instructions::InstructionsDisplay;
 use ratatui::{
-    layout::{Constraint, Layout},
+    layout::{Constraint, Layout, Rect},
     Frame,
 };
 use suite_progress::SuiteProgressDisplay;
@@ -22,6 +23,7 @@ use waiting_tests::WaitingTestDisplay;
 
 pub mod completed_tests;
 pub mod current_test;
+pub mod instructions;
 pub mod suite_progress;
 pub mod user_text_input;
 pub mod waiting_tests;
@@ -48,6 +50,10 @@ impl Screen for RunningScreen {
             Box::new(SuiteProgressDisplay::new()),
         );
         components.insert(Id::RunningTextInput, Box::new(UserTextInput::new()));
+        components.insert(
+            Id::RunningInstructions,
+            Box::new(InstructionsDisplay::new()),
+        );
         components.insert(Id::RunningCurrentTest, Box::new(CurrentTestDisplay::new()));
         components.insert(Id::RunningWaitingTests, Box::new(WaitingTestDisplay::new()));
         components.insert(
@@ -61,6 +67,7 @@ impl Screen for RunningScreen {
     fn deactivate(&mut self, components: &mut std::collections::HashMap<Id, Box<dyn Component>>) {
         components.remove(&Id::RunningSuiteProgress);
         components.remove(&Id::RunningTextInput);
+        components.remove(&Id::RunningInstructions);
         components.remove(&Id::RunningCurrentTest);
         components.remove(&Id::RunningWaitingTests);
         components.remove(&Id::RunningCompletedTests);
@@ -88,14 +95,27 @@ impl Screen for RunningScreen {
         components: &mut std::collections::HashMap<Id, Box<dyn Component>>,
         state: &SuiteDataCollectionRaw,
     ) -> Result<()> {
-        let [test_progress, operator, current_test, lists_of_tests] = Layout::vertical([
+        let [test_progress, operator, tests] = Layout::vertical([
             Constraint::Length(1),
             Constraint::Length(3),
-            Constraint::Length(10),
             Constraint::Min(1),
         ])
         .areas(frame.area());
 
+        // Instructions share the space below the prompt while there are any
+        let (tests, instructions) = match state.instructions {
+            Some(_) => {
+                let [tests, instructions] =
+                    Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+                        .areas(tests);
+                (tests, instructions)
+            }
+            None => (tests, Rect::default()),
+        };
+
+        let [current_test, lists_of_tests] =
+            Layout::vertical([Constraint::Length(10), Constraint::Min(1)]).areas(tests);
+
         let [completed_list, waiting_list] =
             Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
                 .areas(lists_of_tests);
@@ -110,6 +130,11 @@ impl Screen for RunningScreen {
             .unwrap()
             .draw(frame, operator, state)?;
 
+        components
+            .get_mut(&Id::RunningInstructions)
+            .unwrap()
+            .draw(frame, instructions, state)?;
+
         components
             .get_mut(&Id::RunningCurrentTest)
             .unwrap()
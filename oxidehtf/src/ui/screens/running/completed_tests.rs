@@ -1,17 +1,18 @@
 use ratatui::{
     layout::Rect,
     style::Style,
-    text::Text,
+    text::{Line, Span, Text},
     widgets::{Block, Paragraph},
     Frame,
 };
 
 use crate::{
     common::*,
-    test_runner::{SuiteDataCollectionRaw, TestState},
+    test_runner::{SuiteDataCollectionRaw, TestDone, TestState},
 };
 
 use super::Component;
+use crate::ui::screens::done_style;
 
 pub struct CompletedTestDisplay {}
 
@@ -25,12 +26,23 @@ impl CompletedTestDisplay {
             .current_suite()
             .test_data
             .iter()
-            .filter(|test| match test.state {
-                TestState::Done(_) => true,
-                _ => false,
-            })
+            .filter(|test| matches!(test.state, TestState::Done(_)))
             .rev()
-            .map(|test| format!("{} - {}", test.name, test.state));
+            .map(|test| {
+                let style = match &test.state {
+                    TestState::Done(done) => done_style(done),
+                    _ => Style::default(),
+                };
+
+                let mut line = Line::from(vec![
+                    Span::raw(format!("{} - ", test.name)),
+                    Span::styled(test.state.to_string(), style),
+                ]);
+                if let TestState::Done(TestDone::Skipped(reason)) = &test.state {
+                    line.push_span(Span::raw(format!(" ({})", reason)));
+                }
+                line
+            });
 
         let test_list = Paragraph::new(Text::from_iter(completed_tests)).block(
             Block::bordered()
@@ -11,7 +11,10 @@ use crate::ui::screens::components::Attribute;
 use crate::{
     common::*,
     event_handlers::MovementHandler,
-    test_runner::{SuiteDataCollectionRaw, TestState},
+    test_runner::{
+        context::measurement::{Axis, DataTypes, MeasurementStatus},
+        SuiteDataCollectionRaw, TestState,
+    },
 };
 
 use super::Component;
@@ -56,7 +59,7 @@ impl CurrentTestDisplay {
         };
 
         let max_offset = self.total_measurements - self.current_rows_seen;
-        *offset = (*offset).clamp(0 as usize, max_offset);
+        *offset = (*offset).clamp(0_usize, max_offset);
     }
 
     fn render_current_test(
@@ -90,20 +93,36 @@ impl CurrentTestDisplay {
         if current_test.name == current_test_name {
             for data in &current_test.user_data {
                 let name = data.0.clone();
-                let Some(value) = data.1.value.clone() else {
-                    break;
+                let value = match (&data.1.value, &data.1.raw_value) {
+                    (Some(value), Some(raw)) => format!("{} (raw {})", value, raw),
+                    (Some(value), None) => value.to_string(),
+                    (None, Some(raw)) => format!("Invalid (raw {})", raw),
+                    (None, None) => "Not Set".into(),
                 };
-                let value = format!("{}", value);
-                let unit = data.1.unit.clone().unwrap_or("None".into());
-                let row = Row::new(vec![name.into(), value, unit.into()]);
-                rows.push(row);
+                let unit = data
+                    .1
+                    .unit
+                    .as_ref()
+                    .map_or("None".into(), |u| u.to_string());
+                let row = Row::new(vec![name, value, unit]);
+                rows.push((row, data.1.status == MeasurementStatus::Marginal));
+
+                if let Some(DataTypes::Dimensioned { axes, rows: points }) = &data.1.value {
+                    rows.extend(dimensioned_rows(axes, points).map(|row| (row, false)));
+                }
+                if let Some(statistics) = data.1.statistics() {
+                    let row = Row::new(vec![String::new(), statistics.to_string(), String::new()]);
+                    rows.push((row, false));
+                }
             }
         }
 
         self.total_measurements = rows.len();
 
-        let rows = rows.iter_mut().enumerate().map(|(i, r)| {
-            if i % 2 == 0 {
+        let rows = rows.iter_mut().enumerate().map(|(i, (r, marginal))| {
+            if *marginal {
+                r.clone().black().on_yellow()
+            } else if i % 2 == 0 {
                 r.clone().black().on_gray()
             } else {
                 r.clone()
@@ -158,6 +177,43 @@ impl CurrentTestDisplay {
     }
 }
 
+/// Points shown under a dimensioned measurement before it's cut short
+const MAX_POINTS_SHOWN: usize = 5;
+
+fn dimensioned_rows<'a>(
+    axes: &'a [Axis],
+    points: &'a [Vec<f64>],
+) -> impl Iterator<Item = Row<'static>> + 'a {
+    let header = Row::new(vec![
+        String::new(),
+        axes.iter()
+            .map(|a| a.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" | "),
+        axes.iter()
+            .map(|a| a.unit.as_ref().map_or("-".into(), |u| u.to_string()))
+            .collect::<Vec<_>>()
+            .join(" | "),
+    ])
+    .italic();
+
+    let shown = points.iter().take(MAX_POINTS_SHOWN).map(|point| {
+        let values: Vec<String> = point.iter().map(|v| format!("{:.4}", v)).collect();
+        Row::new(vec![String::new(), values.join(" | "), String::new()])
+    });
+
+    let hidden = points.len().saturating_sub(MAX_POINTS_SHOWN);
+    let more = (hidden > 0).then(|| {
+        Row::new(vec![
+            String::new(),
+            format!("... {} more", hidden),
+            String::new(),
+        ])
+    });
+
+    std::iter::once(header).chain(shown).chain(more)
+}
+
 impl Component for CurrentTestDisplay {
     fn name(&self) -> &str {
         "Current Test Measurements"
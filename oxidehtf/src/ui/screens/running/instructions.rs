@@ -0,0 +1,382 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::{cursor::MoveTo, QueueableCommand};
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::{
+    common::*,
+    test_runner::{context::user_text_input::instructions::Instructions, SuiteDataCollectionRaw},
+};
+
+use super::Component;
+
+/// How the terminal shows images, if it can
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImageProtocol {
+    Kitty,
+    Iterm2,
+}
+
+impl ImageProtocol {
+    fn detect() -> Option<Self> {
+        let var = |name| std::env::var(name).unwrap_or_default();
+
+        if !var("KITTY_WINDOW_ID").is_empty() || var("TERM") == "xterm-kitty" {
+            Some(Self::Kitty)
+        } else if matches!(var("TERM_PROGRAM").as_str(), "iTerm.app" | "WezTerm") {
+            Some(Self::Iterm2)
+        } else {
+            None
+        }
+    }
+
+    /// Kitty is only sent PNGs, it would have to be sent decoded pixels
+    /// otherwise
+    fn can_show(self, path: &Path) -> bool {
+        match self {
+            Self::Kitty => path
+                .extension()
+                .is_some_and(|e| e.eq_ignore_ascii_case("png")),
+            Self::Iterm2 => true,
+        }
+    }
+}
+
+pub struct InstructionsDisplay {
+    protocol: Option<ImageProtocol>,
+    /// The last image read, `None` if it couldn't be
+    loaded: Option<(PathBuf, Option<Vec<u8>>)>,
+    /// Where the image should be after this frame
+    wanted: Option<(PathBuf, Rect)>,
+    /// Where the image was last drawn
+    shown: Option<(PathBuf, Rect)>,
+}
+
+impl InstructionsDisplay {
+    pub fn new() -> Self {
+        Self {
+            protocol: ImageProtocol::detect(),
+            loaded: None,
+            wanted: None,
+            shown: None,
+        }
+    }
+
+    /// The image's bytes if the terminal can show it
+    fn image(&mut self, instructions: &Instructions) -> Option<&[u8]> {
+        let path = instructions.image.as_ref()?;
+        if !self.protocol?.can_show(path) {
+            return None;
+        }
+
+        if self
+            .loaded
+            .as_ref()
+            .is_none_or(|(loaded, _)| loaded != path)
+        {
+            let bytes = std::fs::read(path)
+                .inspect_err(|e| error!("Failed to read image {}: {}", path.display(), e))
+                .ok();
+            self.loaded = Some((path.clone(), bytes));
+        }
+
+        self.loaded.as_ref()?.1.as_deref()
+    }
+
+    fn render_instructions(&mut self, frame: &mut Frame, area: Rect, instructions: &Instructions) {
+        let block = Block::bordered()
+            .title(instructions.title.as_str())
+            .title_style(Style::default().bold());
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let mut lines = markdown_lines(&instructions.text);
+
+        let image_size = self.image(instructions).map(png_size);
+        let text_area = match (image_size, &instructions.image) {
+            (Some(size), Some(path)) => {
+                let [text_area, image_area] =
+                    Layout::vertical([Constraint::Min(1), Constraint::Percentage(60)]).areas(inner);
+                let image_area = fit(image_area, size);
+
+                // The terminal draws the image, ratatui leaves its cells alone
+                for position in image_area.positions() {
+                    frame.buffer_mut()[position].set_skip(true);
+                }
+                self.wanted = Some((path.clone(), image_area));
+                text_area
+            }
+            _ => {
+                if let Some(art) = instructions.fallback() {
+                    lines.push(Line::default());
+                    lines.extend(art.lines().map(|l| Line::from(l.to_string())));
+                }
+                inner
+            }
+        };
+
+        frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), text_area);
+    }
+
+    fn write_image(&self, out: &mut dyn Write, area: Rect, image: &[u8]) -> Result<()> {
+        let data = STANDARD.encode(image);
+        out.queue(MoveTo(area.x, area.y))?;
+
+        match self.protocol {
+            Some(ImageProtocol::Kitty) => {
+                let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    let more = u8::from(i + 1 < chunks.len());
+                    let chunk = std::str::from_utf8(chunk)?;
+                    if i == 0 {
+                        write!(
+                            out,
+                            "\x1b_Gf=100,a=T,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                            area.width, area.height, more, chunk
+                        )?;
+                    } else {
+                        write!(out, "\x1b_Gm={};{}\x1b\\", more, chunk)?;
+                    }
+                }
+            }
+            Some(ImageProtocol::Iterm2) => write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+                image.len(),
+                area.width,
+                area.height,
+                data
+            )?,
+            None => (),
+        }
+        Ok(())
+    }
+}
+
+impl Component for InstructionsDisplay {
+    fn name(&self) -> &str {
+        "Instructions"
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect, data: &SuiteDataCollectionRaw) -> Result<()> {
+        self.wanted = None;
+        if let Some(instructions) = &data.instructions {
+            self.render_instructions(frame, area, instructions);
+        }
+        Ok(())
+    }
+
+    fn draw_graphics(&mut self, out: &mut dyn Write) -> Result<()> {
+        if self.wanted == self.shown {
+            return Ok(());
+        }
+
+        // Kitty images sit above the text, they don't go away when it's redrawn
+        if self.shown.is_some() && self.protocol == Some(ImageProtocol::Kitty) {
+            write!(out, "\x1b_Ga=d,q=2\x1b\\")?;
+        }
+
+        if let Some((path, area)) = &self.wanted {
+            if let Some((_, Some(image))) = self.loaded.as_ref().filter(|(p, _)| p == path) {
+                self.write_image(out, *area, image)?;
+            }
+        }
+
+        out.flush()?;
+        self.shown = self.wanted.clone();
+        Ok(())
+    }
+}
+
+impl Drop for InstructionsDisplay {
+    fn drop(&mut self) {
+        if self.shown.is_some() && self.protocol == Some(ImageProtocol::Kitty) {
+            let mut out = std::io::stdout();
+            let _ = write!(out, "\x1b_Ga=d,q=2\x1b\\");
+            let _ = out.flush();
+        }
+    }
+}
+
+/// Styles `# headings`, `- bullets`, `**bold**` and `` `code` ``, other lines
+/// are kept as they are
+fn markdown_lines(text: &str) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') {
+                let heading = trimmed.trim_start_matches('#').trim();
+                Line::styled(heading.to_string(), Style::default().bold().underlined())
+            } else if let Some(item) = trimmed
+                .strip_prefix("- ")
+                .or_else(|| trimmed.strip_prefix("* "))
+            {
+                let indent = " ".repeat(line.len() - trimmed.len());
+                let mut spans = vec![Span::raw(format!("{}• ", indent))];
+                spans.extend(inline_spans(item));
+                Line::from(spans)
+            } else {
+                Line::from(inline_spans(line))
+            }
+        })
+        .collect()
+}
+
+fn inline_spans(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let (mut bold, mut code) = (false, false);
+    let mut current = String::new();
+
+    let mut push = |current: &mut String, bold: bool, code: bool| {
+        if current.is_empty() {
+            return;
+        }
+        let style = match (bold, code) {
+            (_, true) => Style::default().cyan(),
+            (true, false) => Style::default().bold(),
+            (false, false) => Style::default(),
+        };
+        spans.push(Span::styled(std::mem::take(current), style));
+    };
+
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if c == '`' {
+            push(&mut current, bold, code);
+            code = !code;
+            rest = &rest[1..];
+        } else if !code && rest.starts_with("**") {
+            push(&mut current, bold, code);
+            bold = !bold;
+            rest = &rest[2..];
+        } else {
+            current.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    push(&mut current, bold, code);
+
+    spans
+}
+
+/// Width and height in pixels from a PNG header
+fn png_size(image: &[u8]) -> Option<(u32, u32)> {
+    let header = image.get(..24)?;
+    if &header[1..4] != b"PNG" {
+        return None;
+    }
+    let width = u32::from_be_bytes(header[16..20].try_into().ok()?);
+    let height = u32::from_be_bytes(header[20..24].try_into().ok()?);
+    Some((width, height))
+}
+
+/// The largest part of `area` with the image's aspect ratio, taking cells as
+/// twice as tall as they are wide. The size comes from the image's header, so
+/// it's only trusted as far as `area` goes.
+fn fit(area: Rect, size: Option<(u32, u32)>) -> Rect {
+    let Some((width, height)) = size.filter(|(w, h)| *w > 0 && *h > 0) else {
+        return area;
+    };
+    let (width, height) = (u64::from(width), u64::from(height));
+
+    let rows = u64::from(area.width) * height / width / 2;
+    if rows <= u64::from(area.height) {
+        Rect {
+            height: rows.max(1).min(u64::from(area.height)) as u16,
+            ..area
+        }
+    } else {
+        let cols = u64::from(area.height) * 2 * width / height;
+        Rect {
+            width: cols.max(1).min(u64::from(area.width)) as u16,
+            ..area
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_header(width: u32, height: u32) -> Vec<u8> {
+        let mut header = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        header
+    }
+
+    #[test]
+    fn png_size_reads_the_header() {
+        assert_eq!(png_size(&png_header(640, 480)), Some((640, 480)));
+        assert_eq!(png_size(&png_header(640, 480)[..20]), None);
+        assert_eq!(png_size(b"GIF89a not a png, but long enough"), None);
+        assert_eq!(png_size(&[]), None);
+    }
+
+    #[test]
+    fn fit_keeps_the_aspect_ratio_inside_the_area() {
+        let area = Rect::new(2, 3, 40, 30);
+
+        // Cells are twice as tall as wide, so a square image is half as tall
+        assert_eq!(fit(area, Some((100, 100))), Rect::new(2, 3, 40, 20));
+        assert_eq!(fit(area, Some((100, 400))), Rect::new(2, 3, 15, 30));
+        assert_eq!(fit(area, Some((1000, 1))), Rect::new(2, 3, 40, 1));
+        assert_eq!(fit(area, Some((1, 1000))), Rect::new(2, 3, 1, 30));
+        assert_eq!(fit(area, Some((0, 100))), area);
+        assert_eq!(fit(area, None), area);
+
+        // Sizes from a hostile header mustn't overflow or leave the area
+        assert_eq!(fit(area, Some((1, u32::MAX))), Rect::new(2, 3, 1, 30));
+        assert_eq!(fit(area, Some((u32::MAX, 1))), Rect::new(2, 3, 40, 1));
+        assert_eq!(
+            fit(area, Some((u32::MAX, u32::MAX))),
+            Rect::new(2, 3, 40, 20)
+        );
+        let huge = Rect::new(0, 0, u16::MAX, u16::MAX);
+        assert_eq!(fit(huge, Some((1, u32::MAX))), Rect::new(0, 0, 1, u16::MAX));
+    }
+
+    #[test]
+    fn markdown_is_styled() {
+        let text = "# Probe TP3\n- Hold the **probe** on `TP3`\n  * gently\nplain";
+
+        assert_eq!(
+            markdown_lines(text),
+            [
+                Line::styled("Probe TP3", Style::default().bold().underlined()),
+                Line::from(vec![
+                    Span::raw("• "),
+                    Span::raw("Hold the "),
+                    Span::styled("probe", Style::default().bold()),
+                    Span::raw(" on "),
+                    Span::styled("TP3", Style::default().cyan()),
+                ]),
+                Line::from(vec![Span::raw("  • "), Span::raw("gently")]),
+                Line::from(vec![Span::raw("plain")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn code_spans_keep_their_asterisks() {
+        assert_eq!(
+            inline_spans("`**x**` and **bold `code`**"),
+            [
+                Span::styled("**x**", Style::default().cyan()),
+                Span::raw(" and "),
+                Span::styled("bold ", Style::default().bold()),
+                Span::styled("code", Style::default().cyan()),
+            ]
+        );
+    }
+}
@@ -22,10 +22,7 @@ impl SuiteProgressDisplay {
         let tests_finished = data.inner[data.current]
             .test_data
             .iter()
-            .filter(|test| match test.state {
-                TestState::Done(_) => true,
-                _ => false,
-            })
+            .filter(|test| matches!(test.state, TestState::Done(_)))
             .count() as f64;
 
         let total_tests = data.inner[data.current].test_data.len() as f64;
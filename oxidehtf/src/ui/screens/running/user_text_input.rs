@@ -1,14 +1,21 @@
+use std::time::Instant;
+
 use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Layout, Rect},
     style::Style,
+    text::{Line, Span},
     widgets::{Block, Paragraph},
     Frame,
 };
-use tokio::sync::oneshot;
 
 use crate::{
-    common::*, event_handlers::TextInputHandler, test_runner::SuiteDataCollectionRaw,
+    common::*,
+    event_handlers::TextInputHandler,
+    test_runner::{
+        context::user_text_input::prompt::{Answer, AnswerSender, Prompt, PromptKind},
+        SuiteDataCollectionRaw,
+    },
     ui::screens::components::Attribute,
 };
 
@@ -16,9 +23,16 @@ use super::Component;
 
 pub struct UserTextInput {
     txt_input: tui_input::Input,
-    prompt: String,
+    prompt: Option<Prompt>,
+    /// Why the last answer was rejected, cleared once the answer is edited
+    error: Option<String>,
+    /// When an unanswered prompt times out
+    deadline: Option<Instant>,
     is_focused: bool,
-    reply: Option<oneshot::Sender<String>>,
+    reply: Option<AnswerSender>,
+    /// The button picked with the arrow keys, for prompts answered with
+    /// buttons
+    selected: usize,
 }
 
 impl UserTextInput {
@@ -27,12 +41,57 @@ impl UserTextInput {
     pub fn new() -> Self {
         Self {
             txt_input: Default::default(),
-            prompt: Self::DEFAULT_PROMPT.into(),
+            prompt: None,
+            error: None,
+            deadline: None,
             is_focused: false,
             reply: None,
+            selected: 0,
+        }
+    }
+
+    /// The buttons the prompt is answered with, none for typed answers
+    fn buttons(&self) -> &'static [&'static str] {
+        match self.prompt.as_ref().map(|p| &p.kind) {
+            Some(PromptKind::Acknowledge) => &["Continue"],
+            Some(PromptKind::PassFail) => &["PASS", "FAIL"],
+            _ => &[],
         }
     }
 
+    /// What pressing the selected button answers, the text input is the
+    /// comment for pass/fail prompts
+    fn button_answer(&self) -> Option<String> {
+        let button = self.buttons().get(self.selected)?;
+        let comment = self.txt_input.value().trim();
+
+        Some(match (*button, comment) {
+            ("Continue", _) => "ok".into(),
+            (verdict, "") => verdict.to_lowercase(),
+            (verdict, comment) => format!("{}: {}", verdict.to_lowercase(), comment),
+        })
+    }
+
+    fn draw_buttons(&self, frame: &mut Frame, area: Rect) {
+        let mut spans = Vec::new();
+        for (i, button) in self.buttons().iter().enumerate() {
+            let style = match *button {
+                "PASS" => Style::default().green(),
+                "FAIL" => Style::default().red(),
+                _ => Style::default(),
+            };
+            let style = if i == self.selected {
+                style.reversed().bold()
+            } else {
+                style
+            };
+            spans.push(Span::styled(format!("[ {} ]", button), style));
+            spans.push(Span::raw(" "));
+        }
+
+        frame.render_widget(Line::from(spans).right_aligned(), area);
+    }
+
     fn draw_input(
         &mut self,
         frame: &mut Frame,
@@ -45,18 +104,56 @@ impl UserTextInput {
             Style::default()
         };
 
-        // keep 2 for borders and 1 for cursor
-        let width = area.width.max(3) - 3;
-        let scroll = self.txt_input.visual_scroll(width as usize);
-        let input = Paragraph::new(self.txt_input.value())
-            .scroll((0, scroll as u16))
-            .block(
-                Block::bordered()
-                    .border_style(border_style)
-                    .title(self.prompt.clone())
-                    .title_style(Style::default().bold()),
-            );
-        frame.render_widget(input, area);
+        let title = self
+            .prompt
+            .as_ref()
+            .map_or(Self::DEFAULT_PROMPT.into(), Prompt::title);
+        let mut block = Block::bordered()
+            .border_style(border_style)
+            .title(title)
+            .title_style(Style::default().bold());
+        if let Some(deadline) = self.deadline {
+            let left = deadline.saturating_duration_since(Instant::now());
+            let countdown = match self.prompt.as_ref().and_then(|p| p.default.as_ref()) {
+                Some(default) => format!("{}s, then '{}'", left.as_secs_f64().ceil(), default),
+                None => format!("{}s", left.as_secs_f64().ceil()),
+            };
+            block = block.title_top(Line::from(countdown).right_aligned());
+        }
+        if let Some(error) = &self.error {
+            block = block.title_bottom(Line::styled(error.as_str(), Style::default().red()));
+        }
+
+        let buttons = self.buttons();
+        if buttons.len() > 1 {
+            block = block.title_bottom(Line::from("←/→ choose, Enter answers").right_aligned());
+        }
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let width: u16 = buttons.iter().map(|b| b.len() as u16 + 5).sum();
+        let [input_area, buttons_area] =
+            Layout::horizontal([Constraint::Min(0), Constraint::Length(width)]).areas(inner);
+        self.draw_buttons(frame, buttons_area);
+
+        // keep 1 for cursor
+        let scroll = self
+            .txt_input
+            .visual_scroll(input_area.width.saturating_sub(1) as usize);
+
+        // Acknowledging takes no text
+        if matches!(
+            self.prompt.as_ref().map(|p| &p.kind),
+            Some(PromptKind::Acknowledge)
+        ) {
+            return Ok(());
+        }
+
+        let input = match (self.txt_input.value(), buttons.is_empty()) {
+            ("", false) => Paragraph::new("Comment (optional)").style(Style::default().dark_gray()),
+            (value, _) => Paragraph::new(value).scroll((0, scroll as u16)),
+        };
+        frame.render_widget(input, input_area);
 
         // Ratatui hides the cursor unless it's explicitly set. Position the  cursor past
         // the end of the input text and one line down from the border to the input line
@@ -78,6 +175,22 @@ impl Component for UserTextInput {
             {
                 Ok(Some(Action::SendInput))
             }
+            // The buttons sit side by side, so the left and right arrows pick
+            // them rather than moving through the comment
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::NONE
+                    && (key.code == KeyCode::Up
+                        || key.code == KeyCode::Left && !self.buttons().is_empty()) =>
+            {
+                Ok(Some(Action::MoveUp))
+            }
+            Event::Key(key)
+                if key.modifiers == KeyModifiers::NONE
+                    && (key.code == KeyCode::Down
+                        || key.code == KeyCode::Right && !self.buttons().is_empty()) =>
+            {
+                Ok(Some(Action::MoveDown))
+            }
             _ => Ok(TextInputHandler::handle_events(event)),
         }
     }
@@ -88,18 +201,50 @@ impl Component for UserTextInput {
                 if !self.is_focused {
                     return Ok(None);
                 }
-                self.txt_input.handle(e.clone());
+                self.txt_input.handle(*e);
+                self.error = None;
+            }
+            Action::MoveUp | Action::MoveDown => {
+                let count = self.buttons().len();
+                if count > 0 {
+                    self.selected = match action {
+                        Action::MoveUp => (self.selected + count - 1) % count,
+                        _ => (self.selected + 1) % count,
+                    };
+                }
             }
             Action::SendInput => {
-                let input = self.txt_input.value_and_reset();
-                self.prompt = Self::DEFAULT_PROMPT.into();
-                let sender = self.reply.take().unwrap();
-                sender.send(input).unwrap();
-                info!("Sent!");
+                let Some(prompt) = &self.prompt else {
+                    return Ok(None);
+                };
+
+                let answer = self
+                    .button_answer()
+                    .unwrap_or_else(|| self.txt_input.value().to_string());
+                match prompt.check(&answer) {
+                    Ok(answer) => {
+                        self.txt_input.reset();
+                        self.prompt = None;
+                        self.error = None;
+                        self.deadline = None;
+                        // The test may have timed out and stopped waiting
+                        if let Some(reply) = self.reply.take() {
+                            let _ = reply.send(Ok(Answer {
+                                text: answer,
+                                scripted: false,
+                            }));
+                        }
+                        info!("Sent!");
+                    }
+                    Err(reason) => self.error = Some(reason),
+                }
                 return Ok(None);
             }
-            Action::UserInputPrompt(v, ref mut c) => {
-                self.prompt = v.clone();
+            Action::OperatorPrompt(prompt, ref mut c) => {
+                self.deadline = prompt.timeout.map(|timeout| Instant::now() + timeout);
+                self.prompt = Some(prompt.clone());
+                self.error = None;
+                self.selected = 0;
                 self.reply = c.take();
             }
             _ => (),
@@ -118,8 +263,111 @@ impl Component for UserTextInput {
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect, data: &SuiteDataCollectionRaw) -> Result<()> {
+        // The test stops listening once the prompt times out
+        if self.reply.as_ref().is_some_and(|reply| reply.is_closed()) {
+            self.txt_input.reset();
+            self.prompt = None;
+            self.error = None;
+            self.deadline = None;
+            self.reply = None;
+        }
+
         self.draw_input(frame, area, data)?;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::KeyEvent;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::TestFailure;
+
+    fn prompted(
+        kind: PromptKind,
+    ) -> (
+        UserTextInput,
+        oneshot::Receiver<Result<Answer, TestFailure>>,
+    ) {
+        let (reply, answer) = oneshot::channel();
+        let mut input = UserTextInput::new();
+        input
+            .update(&mut Action::OperatorPrompt(
+                Prompt::new("LED lit?", kind),
+                Some(reply),
+            ))
+            .unwrap();
+        (input, answer)
+    }
+
+    fn press(input: &mut UserTextInput, code: KeyCode) {
+        let event = Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+        if let Some(mut action) = input.handle_event(&event).unwrap() {
+            input.update(&mut action).unwrap();
+        }
+    }
+
+    #[test]
+    fn buttons_answer_the_prompt() {
+        let (mut input, _answer) = prompted(PromptKind::PassFail);
+        assert_eq!(input.button_answer(), Some("pass".into()));
+
+        input.selected = 1;
+        assert_eq!(input.button_answer(), Some("fail".into()));
+
+        input.txt_input = " LED is dim ".into();
+        assert_eq!(input.button_answer(), Some("fail: LED is dim".into()));
+
+        let (input, _answer) = prompted(PromptKind::Acknowledge);
+        assert_eq!(input.button_answer(), Some("ok".into()));
+
+        let (input, _answer) = prompted(PromptKind::Text);
+        assert_eq!(input.button_answer(), None);
+    }
+
+    #[test]
+    fn arrows_pick_the_button() {
+        let (mut input, _answer) = prompted(PromptKind::PassFail);
+
+        press(&mut input, KeyCode::Right);
+        assert_eq!(input.selected, 1);
+        press(&mut input, KeyCode::Right);
+        assert_eq!(input.selected, 0);
+        press(&mut input, KeyCode::Left);
+        assert_eq!(input.selected, 1);
+        press(&mut input, KeyCode::Up);
+        assert_eq!(input.selected, 0);
+        press(&mut input, KeyCode::Down);
+        assert_eq!(input.selected, 1);
+    }
+
+    #[test]
+    fn arrows_move_through_typed_answers() {
+        let (mut input, _answer) = prompted(PromptKind::Text);
+        input.is_focused = true;
+
+        press(&mut input, KeyCode::Char('a'));
+        press(&mut input, KeyCode::Char('c'));
+        press(&mut input, KeyCode::Left);
+        press(&mut input, KeyCode::Char('b'));
+        assert_eq!(input.txt_input.value(), "abc");
+        assert_eq!(input.selected, 0);
+    }
+
+    #[test]
+    fn enter_sends_the_selected_button() {
+        let (mut input, mut answer) = prompted(PromptKind::PassFail);
+        input.txt_input = "LED is dim".into();
+
+        press(&mut input, KeyCode::Right);
+        press(&mut input, KeyCode::Enter);
+
+        let answer = answer.try_recv().unwrap().unwrap();
+        assert_eq!(answer.text, "fail: LED is dim");
+        assert!(!answer.scripted);
+        assert!(input.prompt.is_none());
+    }
+}
@@ -24,10 +24,7 @@ impl WaitingTestDisplay {
         let waiting_tests: Vec<Line> = data.inner[data.current]
             .test_data
             .iter()
-            .filter(|test| match test.state {
-                TestState::InQueue | TestState::Running(_) => true,
-                _ => false,
-            })
+            .filter(|test| matches!(test.state, TestState::InQueue | TestState::Running(_)))
             .map(|test| Line::from(format!("{} - {}", test.name, test.state)))
             .collect();
 
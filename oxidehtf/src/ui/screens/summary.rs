@@ -1,12 +1,9 @@
 use std::collections::HashMap;
 
 use super::components::Component;
-use super::Screen;
+use super::{done_style, outcome_style, Screen};
 use crate::test_runner::{TestDone, TestState};
 use crate::{app::Id, common::*, test_runner::SuiteDataCollectionRaw};
-use color_eyre::owo_colors::OwoColorize;
-use ratatui::layout::Alignment;
-use ratatui::style::{Modifier, Stylize};
 use ratatui::text::{Line, Span, Text};
 use ratatui::{
     layout::{Constraint, Flex, Layout},
@@ -55,30 +52,48 @@ impl Screen for SummaryScreen {
 
         let title = Line::from(Span::styled("Summary", Style::default().bold()));
         text.push_line(title);
+        let outcome = state.outcome();
+        text.push_line(Span::styled(
+            outcome.to_string(),
+            outcome_style(outcome).bold(),
+        ));
         text.push_line("");
 
         for suite in &state.inner {
-            let suite_name = Span::styled(format!("{}", suite.name), Style::default().underlined());
+            let suite_outcome = suite.outcome();
+            let suite_name = Line::from(vec![
+                Span::styled(suite.name.to_string(), Style::default().underlined()),
+                Span::raw(" - "),
+                Span::styled(suite_outcome.to_string(), outcome_style(suite_outcome)),
+            ]);
 
             let tests = suite.test_data.iter().map(|f| {
                 let style = match f.state {
-                    TestState::Done(ref d) => match d {
-                        TestDone::Failed(_) => Style::default().red(),
-                        TestDone::Passed => Style::default().green(),
-                    },
+                    TestState::Done(ref d) => done_style(d),
                     _ => panic!("Not all tests are done"),
                 };
 
-                let line = Line::from(vec![
+                let mut line = Line::from(vec![
                     Span::from(f.name),
                     Span::raw(" - "),
                     Span::styled(format!("{}", f.state), style),
                 ]);
+
+                if let TestState::Done(TestDone::Skipped(reason)) = &f.state {
+                    line.push_span(Span::raw(format!(" ({})", reason)));
+                }
+
                 line
             });
 
             text.push_line(suite_name);
             text.extend(tests);
+            text.extend(
+                suite
+                    .lifecycle_errors
+                    .iter()
+                    .map(|e| Line::styled(e.to_string(), Style::default().magenta())),
+            );
             text.push_line("");
         }
 
@@ -22,7 +22,7 @@ pub struct SuitesDisplay {
     scrollbar_state: ScrollbarState,
     is_focused: bool,
     current_rows_seen: usize,
-    total_suites: usize,
+    total_rows: usize,
 }
 
 impl SuitesDisplay {
@@ -31,7 +31,7 @@ impl SuitesDisplay {
             table_state: TableState::default(),
             is_focused: false,
             current_rows_seen: 0,
-            total_suites: 0,
+            total_rows: 0,
             scrollbar_state: ScrollbarState::new(0),
         }
     }
@@ -51,8 +51,8 @@ impl SuitesDisplay {
             }
         };
 
-        let max_offset = self.total_suites - self.current_rows_seen;
-        *offset = (*offset).clamp(0 as usize, max_offset);
+        let max_offset = self.total_rows.saturating_sub(self.current_rows_seen);
+        *offset = (*offset).clamp(0_usize, max_offset);
     }
 
     fn render_suites(&mut self, frame: &mut Frame, area: Rect, data: &SuiteDataCollectionRaw) {
@@ -65,19 +65,34 @@ impl SuitesDisplay {
             let priority = suite.priority.to_string();
             let position = (index + 1).to_string();
             let num_tests = suite.test_data.len().to_string();
-            let row = vec![position, name, priority, num_tests];
-            rows.push(Row::from_iter(row));
-        }
-
-        self.total_suites = data.inner.len();
-
-        let rows = rows.iter_mut().enumerate().map(|(i, r)| {
-            if i % 2 == 0 {
-                r.clone()
+            let row = vec![position, name, priority, num_tests, String::new()];
+            let row = Row::from_iter(row);
+            rows.push(if index % 2 == 0 {
+                row
             } else {
-                r.clone().black().on_gray()
+                row.black().on_gray()
+            });
+
+            // Declared measurements are the only ones known before the run
+            for test in suite.test_data.iter().filter(|t| !t.user_data.is_empty()) {
+                let measurements = test
+                    .user_data
+                    .keys()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let row = vec![
+                    String::new(),
+                    format!("  {}", test.name),
+                    String::new(),
+                    String::new(),
+                    measurements,
+                ];
+                rows.push(Row::from_iter(row).dark_gray());
             }
-        });
+        }
+
+        self.total_rows = rows.len();
 
         let border_style = if self.is_focused {
             Style::default().yellow()
@@ -91,12 +106,19 @@ impl SuitesDisplay {
             Constraint::Min(5),
             Constraint::Min(5),
             Constraint::Min(5),
+            Constraint::Fill(2),
         ];
         let table = Table::new(rows, widths)
             .block(Block::bordered().border_style(border_style))
             .header(
-                Row::new(vec!["Run Order", "Name", "Priority", "No. Tests"])
-                    .style(Style::new().underlined()),
+                Row::new(vec![
+                    "Run Order",
+                    "Name",
+                    "Priority",
+                    "No. Tests",
+                    "Declared Measurements",
+                ])
+                .style(Style::new().underlined()),
             )
             .highlight_symbol(">>");
 
@@ -110,7 +132,7 @@ impl SuitesDisplay {
 
         self.scrollbar_state = self
             .scrollbar_state
-            .content_length(self.total_suites)
+            .content_length(self.total_rows)
             .viewport_content_length(self.current_rows_seen);
 
         frame.render_stateful_widget(
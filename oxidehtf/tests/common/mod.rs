@@ -0,0 +1,69 @@
+// Each test binary only uses some of the helpers
+#![allow(dead_code)]
+
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use oxidehtf::{PromptResponder, RunConfig, RunRecord, TestOutcome, TestRecord};
+
+/// Runs the suites registered in the calling test binary without a TUI and
+/// returns the run's record
+pub fn run(config: RunConfig) -> RunRecord {
+    run_with(config, |_: &str| None)
+}
+
+/// Like [`run`], answering operator prompts with `responder`
+pub fn run_with(config: RunConfig, responder: impl PromptResponder + 'static) -> RunRecord {
+    let record = Arc::new(Mutex::new(None));
+
+    let saved = record.clone();
+    oxidehtf::run_tests_with(config.headless(responder).sink(move |record: &RunRecord| {
+        *saved.lock().unwrap() = Some(record.clone());
+        Ok(())
+    }))
+    .unwrap();
+
+    let record = record.lock().unwrap().take();
+    record.expect("no record was written")
+}
+
+/// The test named `name` in any suite
+pub fn test<'a>(record: &'a RunRecord, name: &str) -> &'a TestRecord {
+    record
+        .suites
+        .iter()
+        .flat_map(|suite| &suite.tests)
+        .find(|test| test.name == name)
+        .unwrap_or_else(|| panic!("no test named {}", name))
+}
+
+/// The test's outcome and the kind of its failure, if any
+pub fn result(record: &RunRecord, name: &str) -> (TestOutcome, Option<String>) {
+    let test = test(record, name);
+    (test.outcome, test.failure.as_ref().map(|f| f.kind.clone()))
+}
+
+/// A directory under the system temp dir, removed with everything in it when
+/// dropped, so a failed assert doesn't leave it behind
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` keeps tests running at the same time apart
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("oxidehtf-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    pub fn join(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.0.join(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
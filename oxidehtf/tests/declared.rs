@@ -0,0 +1,56 @@
+use oxidehtf::{
+    MeasurementOutcome, RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome,
+};
+
+mod common;
+
+struct Suite;
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test(measurements = ["Link Quality", "Antenna Gain"])]
+    fn declared_not_set(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.measurements.declare("Noise Floor");
+        context
+            .measurements
+            .measure("Link Quality")
+            .at_least(0.8)
+            .set(0.93)?;
+        Ok(())
+    }
+
+    #[test(measurements = ["Link Quality"])]
+    fn declared_and_set(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.measurements.measure("Link Quality").set(0.93)?;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+#[test]
+fn declared_measurements_must_be_set() {
+    let record = common::run(RunConfig::new());
+
+    assert_eq!(
+        common::result(&record, "declared_not_set"),
+        (TestOutcome::Failed, Some("MeasurementNotSet".to_string()))
+    );
+    let test = common::test(&record, "declared_not_set");
+    let unset: Vec<&str> = test
+        .measurements
+        .iter()
+        .filter(|m| m.outcome == MeasurementOutcome::Unset)
+        .map(|m| m.name.as_str())
+        .collect();
+    assert_eq!(unset, ["Antenna Gain", "Noise Floor"]);
+
+    assert_eq!(
+        common::result(&record, "declared_and_set"),
+        (TestOutcome::Passed, None)
+    );
+}
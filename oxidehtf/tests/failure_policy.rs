@@ -0,0 +1,86 @@
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct InterlockSuite;
+
+#[oxidehtf_macros::tests(on_failure = "stop_suite")]
+impl InterlockSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn interlock_open(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(true, false);
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_after_stop_suite(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for InterlockSuite {}
+
+struct CalibrationSuite;
+
+#[oxidehtf_macros::tests(1, on_failure = "stop_run")]
+impl CalibrationSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn runs_after_stop_suite(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test]
+    fn reference_out_of_range(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(3.3, 5.0);
+        Ok(())
+    }
+
+    #[test]
+    fn skipped_after_stop_run(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for CalibrationSuite {}
+
+struct FinalSuite;
+
+#[oxidehtf_macros::tests(2)]
+impl FinalSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn never_runs(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for FinalSuite {}
+
+#[test]
+fn failure_policies_skip_the_remaining_tests() {
+    let record = common::run(RunConfig::new());
+
+    let failed = (TestOutcome::Failed, Some("AssertionFailed".to_string()));
+    let skipped = (TestOutcome::Skipped, None);
+    assert_eq!(common::result(&record, "interlock_open"), failed);
+    assert_eq!(common::result(&record, "skipped_after_stop_suite"), skipped);
+    assert_eq!(
+        common::result(&record, "runs_after_stop_suite"),
+        (TestOutcome::Passed, None)
+    );
+    assert_eq!(common::result(&record, "reference_out_of_range"), failed);
+    assert_eq!(common::result(&record, "skipped_after_stop_run"), skipped);
+    assert_eq!(common::result(&record, "never_runs"), skipped);
+    assert!(common::test(&record, "never_runs").skip_reason.is_some());
+}
@@ -0,0 +1,109 @@
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct FixtureSuite {
+    fixture_connected: bool,
+}
+
+#[oxidehtf_macros::tests]
+impl FixtureSuite {
+    fn new() -> Self {
+        Self {
+            fixture_connected: false,
+        }
+    }
+
+    #[test]
+    fn before_test_fails(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test]
+    fn after_test_fails(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        self.fixture_connected = true;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for FixtureSuite {
+    fn before_test(&mut self) -> color_eyre::eyre::Result<()> {
+        if !self.fixture_connected {
+            self.fixture_connected = true;
+            color_eyre::eyre::bail!("Fixture not connected");
+        }
+        Ok(())
+    }
+
+    fn after_test(&mut self) -> color_eyre::eyre::Result<()> {
+        color_eyre::eyre::ensure!(!self.fixture_connected, "Fixture left clamped");
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> color_eyre::eyre::Result<()> {
+        panic!("Fixture release jammed");
+    }
+}
+
+struct BrokenSetupSuite;
+
+#[oxidehtf_macros::tests(1)]
+impl BrokenSetupSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn needs_setup(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for BrokenSetupSuite {
+    fn setup(&mut self) -> color_eyre::eyre::Result<()> {
+        color_eyre::eyre::bail!("Power supply not found")
+    }
+}
+
+struct LaterSuite;
+
+#[oxidehtf_macros::tests(2)]
+impl LaterSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn still_runs(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for LaterSuite {}
+
+#[test]
+fn hook_failures_error_and_the_run_continues() {
+    let record = common::run(RunConfig::new());
+
+    let errored = (TestOutcome::Errored, Some("LifecycleFailed".to_string()));
+    assert_eq!(common::result(&record, "before_test_fails"), errored);
+    assert_eq!(common::result(&record, "after_test_fails"), errored);
+    assert_eq!(common::result(&record, "needs_setup"), errored);
+    assert_eq!(
+        common::result(&record, "still_runs"),
+        (TestOutcome::Passed, None)
+    );
+
+    let lifecycle_errors = |suite: usize| -> Vec<String> {
+        record.suites[suite]
+            .lifecycle_errors
+            .iter()
+            .map(|e| e.message.clone())
+            .collect()
+    };
+    assert_eq!(record.suites[0].outcome, TestOutcome::Errored);
+    assert!(lifecycle_errors(0)[0].contains("Fixture release jammed"));
+    assert_eq!(record.suites[1].outcome, TestOutcome::Errored);
+    assert!(lifecycle_errors(1)[0].contains("Power supply not found"));
+    assert_eq!(record.outcome, TestOutcome::Errored);
+}
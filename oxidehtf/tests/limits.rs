@@ -0,0 +1,48 @@
+use oxidehtf::{LimitSpec, LimitsFile};
+
+mod common;
+
+const TOML: &str = r#"
+[PowerSuite.rail_3v3."Supply Voltage"]
+min = 3.2
+max = 3.4
+marginal = [3.25, 3.35]
+unit = "V"
+"#;
+
+const JSON: &str = r#"{
+  "PowerSuite": {
+    "rail_3v3": {
+      "Supply Voltage": { "min": 3.2, "max": 3.4, "marginal": [3.25, 3.35], "unit": "V" }
+    }
+  }
+}"#;
+
+#[test]
+fn limits_file_formats() {
+    let dir = common::TempDir::new("limits");
+
+    let expected = LimitSpec {
+        min: Some(3.2),
+        max: Some(3.4),
+        marginal: Some([3.25, 3.35]),
+        unit: Some("V".into()),
+    };
+
+    for (name, contents) in [("limits.toml", TOML), ("limits.json", JSON)] {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        let limits = LimitsFile::load(&path).unwrap();
+
+        assert_eq!(
+            limits.spec("PowerSuite", "rail_3v3", "Supply Voltage"),
+            Some(&expected)
+        );
+        assert_eq!(limits.spec("PowerSuite", "rail_3v3", "Core Rail"), None);
+        assert_eq!(limits.sha256().len(), 64);
+    }
+
+    let typo = dir.join("typo.toml");
+    std::fs::write(&typo, "[Suite.test.Rail]\nmaximum = 3.4\n").unwrap();
+    assert!(LimitsFile::load(&typo).is_err());
+}
@@ -0,0 +1,23 @@
+use oxidehtf::SysContext;
+use oxidehtf::TestFailure;
+use oxidehtf::TestLifecycle;
+
+struct Suite {}
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self {}
+    }
+
+    #[test(measurements = ["Supply Voltage", 5])]
+    fn test1(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+fn main() -> color_eyre::eyre::Result<()> {
+    oxidehtf::run_tests()
+}
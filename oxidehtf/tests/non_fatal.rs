@@ -0,0 +1,93 @@
+use oxidehtf::{
+    MeasurementOutcome, RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome,
+};
+
+mod common;
+
+struct Suite;
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test(non_fatal)]
+    fn rail_sweep(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        for (rail, nominal, volts) in [("1V8", 1.8, 1.9), ("3V3", 3.3, 3.61), ("5V0", 5.0, 5.02)] {
+            context
+                .measurements
+                .measure(format!("Rail {}", rail))
+                .within_percent(nominal, 5.0)
+                .set(volts)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn optional_check(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Fan Speed")
+            .at_least(1200.0)
+            .non_fatal()
+            .set(950.0)?;
+        context
+            .measurements
+            .measure("Board Temperature")
+            .in_range(10.0, 60.0)
+            .set(41.0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn fatal_check(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .measurements
+            .measure("Leakage")
+            .at_most(0.001)
+            .set(0.004)?;
+        context.measurements.measure("Never Reached").set(1.0)?;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+#[test]
+fn non_fatal_failures_are_recorded_and_the_test_continues() {
+    let record = common::run(RunConfig::new());
+    let outcomes = |name: &str| -> Vec<(String, MeasurementOutcome)> {
+        common::test(&record, name)
+            .measurements
+            .iter()
+            .map(|m| (m.name.clone(), m.outcome))
+            .collect()
+    };
+    let failed = (TestOutcome::Failed, Some("MeasurementFailed".to_string()));
+
+    assert_eq!(common::result(&record, "rail_sweep"), failed);
+    assert_eq!(
+        outcomes("rail_sweep"),
+        [
+            ("Rail 1V8".to_string(), MeasurementOutcome::Fail),
+            ("Rail 3V3".to_string(), MeasurementOutcome::Fail),
+            ("Rail 5V0".to_string(), MeasurementOutcome::Pass),
+        ]
+    );
+
+    assert_eq!(common::result(&record, "optional_check"), failed);
+    assert_eq!(
+        outcomes("optional_check"),
+        [
+            ("Fan Speed".to_string(), MeasurementOutcome::Fail),
+            ("Board Temperature".to_string(), MeasurementOutcome::Pass),
+        ]
+    );
+
+    assert_eq!(common::result(&record, "fatal_check"), failed);
+    assert_eq!(
+        outcomes("fatal_check"),
+        [("Leakage".to_string(), MeasurementOutcome::Fail)]
+    );
+}
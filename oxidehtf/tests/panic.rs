@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct PanickingSuite {
+    tests_finished: usize,
+}
+
+#[oxidehtf_macros::tests]
+impl PanickingSuite {
+    fn new() -> Self {
+        Self { tests_finished: 0 }
+    }
+
+    #[test]
+    fn sync_panic(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        let _value: f64 = "OVERLOAD".parse().expect("Instrument returned garbage");
+        Ok(())
+    }
+
+    #[test]
+    async fn async_panic(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        panic!("Instrument went away");
+    }
+
+    #[test]
+    fn after_panics(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(self.tests_finished, 2);
+        Ok(())
+    }
+}
+
+impl TestLifecycle for PanickingSuite {
+    fn after_test(&mut self) -> color_eyre::eyre::Result<()> {
+        self.tests_finished += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn panics_are_recorded_and_the_suite_continues() {
+    let record = common::run(RunConfig::new());
+
+    let panicked = (TestOutcome::Failed, Some("Panicked".to_string()));
+    assert_eq!(common::result(&record, "sync_panic"), panicked);
+    assert_eq!(common::result(&record, "async_panic"), panicked);
+    assert_eq!(
+        common::result(&record, "after_panics"),
+        (TestOutcome::Passed, None)
+    );
+
+    let failure = common::test(&record, "async_panic")
+        .failure
+        .clone()
+        .unwrap();
+    assert!(
+        failure.message.contains("Instrument went away"),
+        "{}",
+        failure.message
+    );
+    assert!(failure.backtrace.is_some());
+}
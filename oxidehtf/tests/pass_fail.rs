@@ -0,0 +1,53 @@
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct InspectionSuite;
+
+#[oxidehtf_macros::tests]
+impl InspectionSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn led_lit(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.text_input.pass_fail("Is the LED lit?")
+    }
+
+    #[test]
+    fn label_straight(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.text_input.pass_fail("Is the label straight?")
+    }
+}
+
+impl TestLifecycle for InspectionSuite {}
+
+#[test]
+fn operator_verdicts_decide_the_test() {
+    let record = common::run_with(RunConfig::new(), |prompt: &str| {
+        Some(match prompt {
+            "Is the LED lit?" => "pass".to_string(),
+            _ => "FAIL: label is crooked".to_string(),
+        })
+    });
+
+    assert_eq!(
+        common::result(&record, "led_lit"),
+        (TestOutcome::Passed, None)
+    );
+    assert_eq!(
+        common::result(&record, "label_straight"),
+        (TestOutcome::Failed, Some("OperatorRejected".into()))
+    );
+
+    let failure = common::test(&record, "label_straight")
+        .failure
+        .as_ref()
+        .unwrap();
+    assert!(
+        failure.message.contains("label is crooked"),
+        "{}",
+        failure.message
+    );
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct UnattendedSuite;
+
+#[oxidehtf_macros::tests]
+impl UnattendedSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn uses_default(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let closed = context
+            .text_input
+            .prompt("Is the lid closed?")
+            .timeout(Duration::from_millis(100))
+            .default_answer("yes")
+            .confirm()?;
+        assert!(closed);
+        Ok(())
+    }
+
+    #[test]
+    fn times_out(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context
+            .text_input
+            .prompt("Enter the serial")
+            .timeout(Duration::from_millis(100))
+            .request()?;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for UnattendedSuite {}
+
+#[test]
+fn unanswered_prompts_time_out() {
+    // Answers long after the prompts have given up
+    let record = common::run_with(RunConfig::new(), |_: &str| {
+        std::thread::sleep(Duration::from_millis(300));
+        None
+    });
+
+    assert_eq!(
+        common::result(&record, "uses_default"),
+        (TestOutcome::Passed, None)
+    );
+    let input = &common::test(&record, "uses_default").operator_inputs[0];
+    assert_eq!(input.response.as_deref(), Some("yes"));
+    assert!(input.timed_out);
+
+    assert_eq!(
+        common::result(&record, "times_out"),
+        (TestOutcome::Failed, Some("OperatorTimeout".into()))
+    );
+    let input = &common::test(&record, "times_out").operator_inputs[0];
+    assert_eq!(input.response, None);
+    assert!(input.timed_out);
+}
@@ -0,0 +1,255 @@
+use oxidehtf::{
+    AxisRecord, MeasurementOutcome, MeasurementValue, RunRecord, StatisticsRecord, TestOutcome,
+    SCHEMA_VERSION,
+};
+
+mod common;
+
+/// A record as version 1 wrote it, it must keep loading
+const V1_RECORD: &str = r#"{
+  "schema_version": 1,
+  "dut_id": "SN-0001",
+  "station": { "name": "bench-3", "os": "linux", "framework_version": "0.1.0" },
+  "start_time": "2025-01-01T12:00:00Z",
+  "end_time": "2025-01-01T12:00:05Z",
+  "suites": [{
+    "name": "PowerSuite",
+    "priority": 0,
+    "start_time": "2025-01-01T12:00:00Z",
+    "lifecycle_errors": [],
+    "tests": [{
+      "name": "rail_3v3",
+      "outcome": "failed",
+      "duration_secs": 0.12,
+      "failure": {
+        "kind": "MeasurementNotInRange",
+        "message": "Measurement 'Rail' - Expected: 3.2-3.4 - Found: 3.1",
+        "file": "src/power.rs",
+        "line": 42
+      },
+      "measurements": [
+        { "name": "Rail", "value": 3.1, "unit": "V", "range": { "min": 3.2, "max": 3.4 } }
+      ],
+      "operator_inputs": [{ "prompt": "Enter DUT:", "response": "SN-0001" }]
+    }]
+  }]
+}"#;
+
+const RECORD: &str = r#"{
+  "schema_version": 3,
+  "dut_id": "SN-0001",
+  "station": { "name": "bench-3", "os": "linux", "framework_version": "0.1.0" },
+  "start_time": "2025-01-01T12:00:00Z",
+  "end_time": "2025-01-01T12:00:05Z",
+  "limits_file": { "path": "limits/rev-b.toml", "sha256": "9f86d081884c7d65" },
+  "outcome": "failed",
+  "suites": [{
+    "name": "PowerSuite",
+    "priority": 0,
+    "start_time": "2025-01-01T12:00:00Z",
+    "outcome": "failed",
+    "lifecycle_errors": [],
+    "tests": [{
+      "name": "rail_3v3",
+      "outcome": "failed",
+      "duration_secs": 0.12,
+      "failure": {
+        "kind": "MeasurementFailed",
+        "message": "Measurement 'Rail' at src/power.rs:42 - Expected: in range 3.2 to 3.4 - found 3.1",
+        "file": "src/power.rs",
+        "line": 42
+      },
+      "measurements": [
+        {
+          "name": "Rail",
+          "value": 3.1,
+          "raw_value": null,
+          "transforms": [],
+          "statistics": null,
+          "value_type": "f64",
+          "allowed": null,
+          "unit": "V",
+          "range": { "min": 3.2, "max": 3.4 },
+          "validators": ["in range 3.2 to 3.4"],
+          "marginal": { "min": 3.25, "max": 3.35 },
+          "outcome": "fail"
+        }
+      ],
+      "operator_inputs": [{ "prompt": "Enter DUT:", "response": "SN-0001", "timed_out": false, "scripted": false }]
+    }]
+  }]
+}"#;
+
+#[test]
+fn record_round_trip() {
+    let dir = common::TempDir::new("record");
+
+    let documented = dir.join("documented.json");
+    std::fs::write(&documented, RECORD).unwrap();
+    let mut record = RunRecord::load(&documented).unwrap();
+
+    assert_eq!(
+        record.limits_file.as_ref().unwrap().sha256,
+        "9f86d081884c7d65"
+    );
+
+    let test = &record.suites[0].tests[0];
+    assert_eq!(test.outcome, TestOutcome::Failed);
+    assert_eq!(test.skip_reason, None);
+    assert_eq!(test.failure.as_ref().unwrap().line, Some(42));
+    assert_eq!(
+        test.measurements[0].value,
+        Some(MeasurementValue::Number(3.1))
+    );
+
+    let mut sweep = test.measurements[0].clone();
+    sweep.name = "Gain".into();
+    sweep.value = Some(MeasurementValue::Dimensioned {
+        axes: vec![
+            AxisRecord {
+                name: "Frequency".into(),
+                unit: Some("Hz".into()),
+            },
+            AxisRecord {
+                name: "Gain".into(),
+                unit: None,
+            },
+        ],
+        rows: vec![vec![1e3, -0.1], vec![1e6, -1.5]],
+    });
+    record.suites[0].tests[0].measurements.push(sweep);
+
+    let mut noise = record.suites[0].tests[0].measurements[0].clone();
+    noise.name = "Noise".into();
+    noise.value = Some(MeasurementValue::Samples {
+        samples: vec![1.0, 2.0, 3.0],
+    });
+    noise.statistics = Some(StatisticsRecord {
+        count: 3,
+        mean: 2.0,
+        min: 1.0,
+        max: 3.0,
+        stddev: 1.0,
+    });
+    record.suites[0].tests[0].measurements.push(noise);
+
+    let saved = dir.join("saved.json");
+    record.save(&saved).unwrap();
+    assert_eq!(RunRecord::load(&saved).unwrap(), record);
+}
+
+#[test]
+fn v1_record_upgrades() {
+    let dir = common::TempDir::new("record-v1");
+
+    let path = dir.join("v1.json");
+    std::fs::write(&path, V1_RECORD).unwrap();
+    let record = RunRecord::load(&path).unwrap();
+
+    assert_eq!(record.schema_version, SCHEMA_VERSION);
+    assert_eq!(record.outcome, TestOutcome::Failed);
+    assert_eq!(record.suites[0].outcome, TestOutcome::Failed);
+
+    let test = &record.suites[0].tests[0];
+    assert_eq!(test.failure.as_ref().unwrap().kind, "MeasurementFailed");
+    assert_eq!(test.measurements[0].outcome, MeasurementOutcome::Fail);
+    assert!(test.measurements[0].transforms.is_empty());
+    assert!(test.measurements[0].validators.is_empty());
+    assert!(!test.operator_inputs[0].timed_out);
+    assert!(!test.operator_inputs[0].scripted);
+}
+
+#[test]
+fn outcomes_are_added_to_older_records() {
+    let dir = common::TempDir::new("record-outcomes");
+
+    let measurement = |name: &str, value: Option<f64>, outcome: Option<&str>| {
+        let mut measurement = serde_json::json!({
+            "name": name, "value": value, "unit": "V", "range": { "min": 3.2, "max": 3.4 }
+        });
+        if let Some(outcome) = outcome {
+            measurement["outcome"] = outcome.into();
+        }
+        measurement
+    };
+    let suite = |lifecycle_errors: serde_json::Value, measurements: serde_json::Value| {
+        serde_json::json!({
+            "name": "PowerSuite",
+            "priority": 0,
+            "start_time": "2025-01-01T12:00:00Z",
+            "lifecycle_errors": lifecycle_errors,
+            "tests": [{
+                "name": "rail_3v3",
+                "outcome": "passed",
+                "duration_secs": 0.12,
+                "failure": null,
+                "measurements": measurements,
+                "operator_inputs": []
+            }]
+        })
+    };
+    let load = |version: u32, suites: Vec<serde_json::Value>| {
+        let path = dir.join(format!("v{}.json", version));
+        let record = serde_json::json!({
+            "schema_version": version,
+            "dut_id": "SN-0001",
+            "station": { "name": "bench-3", "os": "linux", "framework_version": "0.1.0" },
+            "start_time": "2025-01-01T12:00:00Z",
+            "end_time": "2025-01-01T12:00:05Z",
+            "suites": suites
+        });
+        std::fs::write(&path, record.to_string()).unwrap();
+        RunRecord::load(&path).unwrap()
+    };
+
+    let record = load(
+        1,
+        vec![
+            suite(
+                serde_json::json!([]),
+                serde_json::json!([
+                    measurement("Rail", Some(3.3), None),
+                    measurement("Unset", None, None)
+                ]),
+            ),
+            suite(
+                serde_json::json!([{
+                    "kind": "LifecycleFailed",
+                    "message": "Setup failed - Power supply not found",
+                    "file": null,
+                    "line": null,
+                    "backtrace": null
+                }]),
+                serde_json::json!([]),
+            ),
+        ],
+    );
+    assert_eq!(record.schema_version, SCHEMA_VERSION);
+    assert_eq!(record.outcome, TestOutcome::Errored);
+    assert_eq!(record.suites[0].outcome, TestOutcome::Passed);
+    assert_eq!(record.suites[1].outcome, TestOutcome::Errored);
+    let outcomes: Vec<MeasurementOutcome> = record.suites[0].tests[0]
+        .measurements
+        .iter()
+        .map(|m| m.outcome)
+        .collect();
+    assert_eq!(
+        outcomes,
+        [MeasurementOutcome::Pass, MeasurementOutcome::Unset]
+    );
+
+    // Some version 2 records already have outcomes, they aren't worked out
+    // again
+    let mut marginal = suite(
+        serde_json::json!([]),
+        serde_json::json!([measurement("Rail", Some(3.3), Some("marginal"))]),
+    );
+    marginal["outcome"] = "marginal".into();
+    let record = load(2, vec![marginal]);
+    assert_eq!(record.outcome, TestOutcome::Marginal);
+    assert_eq!(record.suites[0].outcome, TestOutcome::Marginal);
+    assert_eq!(
+        record.suites[0].tests[0].measurements[0].outcome,
+        MeasurementOutcome::Marginal
+    );
+}
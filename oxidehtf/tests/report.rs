@@ -0,0 +1,256 @@
+use std::path::PathBuf;
+
+use std::collections::HashMap;
+
+use oxidehtf::{CsvSink, JunitSink, OutputPath, ReportSink, RunRecord};
+use quick_xml::events::{BytesStart, Event};
+
+mod common;
+
+fn record(dut_id: &str, station: &str) -> RunRecord {
+    serde_json::from_value(serde_json::json!({
+        "schema_version": 3,
+        "dut_id": dut_id,
+        "station": { "name": station, "os": "linux", "framework_version": "0.1.0" },
+        "start_time": "2025-01-01T12:00:00Z",
+        "end_time": "2025-01-01T12:00:05Z",
+        "limits_file": null,
+        "outcome": "passed",
+        "suites": []
+    }))
+    .unwrap()
+}
+
+#[test]
+fn output_path_templating() {
+    let path = OutputPath::new("reports/{station}/{dut_id}-{timestamp}.xml");
+
+    assert_eq!(
+        path.render(&record("SN-0001", "bench-3")),
+        PathBuf::from("reports/bench-3/SN-0001-20250101T120000Z.xml")
+    );
+    assert_eq!(
+        path.render(&record("", "bench-3")),
+        PathBuf::from("reports/bench-3/unknown-20250101T120000Z.xml")
+    );
+}
+
+#[test]
+fn output_path_sanitizing() {
+    let path = OutputPath::new("reports/{station}/{dut_id}.xml");
+
+    assert_eq!(
+        path.render(&record("SN/0001:a", "lab\\bench*3")),
+        PathBuf::from("reports/lab_bench_3/SN_0001_a.xml")
+    );
+    assert_eq!(
+        path.render(&record("../../etc", "..")),
+        PathBuf::from("reports/__/.._.._etc.xml")
+    );
+
+    let path = OutputPath::new("reports/{dut_id}/run.xml");
+    assert_eq!(
+        path.render(&record("..", "bench-3")),
+        PathBuf::from("reports/__/run.xml")
+    );
+    assert_eq!(
+        path.render(&record(".", "bench-3")),
+        PathBuf::from("reports/_/run.xml")
+    );
+}
+
+#[test]
+fn csv_rows() {
+    let mut record = record("SN-0001", "bench-3");
+    record.suites = serde_json::from_value(serde_json::json!([{
+        "name": "PowerSuite",
+        "priority": 0,
+        "start_time": "2025-01-01T12:00:00Z",
+        "outcome": "passed",
+        "lifecycle_errors": [],
+        "tests": [{
+            "name": "rail_3v3",
+            "outcome": "passed",
+            "duration_secs": 0.5,
+            "failure": null,
+            "measurements": [
+                {
+                    "name": "Rail, 3V3",
+                    "value": 3.3,
+                    "raw_value": 3300.0,
+                    "transforms": ["scale by 0.001"],
+                    "statistics": null,
+                    "value_type": "f64",
+                    "allowed": null,
+                    "unit": "V",
+                    "range": { "min": 3.2, "max": 3.4 },
+                    "marginal": null,
+                    "outcome": "pass"
+                },
+                {
+                    "name": "Sweep",
+                    "value": {
+                        "axes": [{ "name": "Frequency", "unit": "Hz" }, { "name": "Gain", "unit": "dB" }],
+                        "rows": [[10.0, 1.5], [20.0, 0.5]]
+                    },
+                    "raw_value": null,
+                    "statistics": null,
+                    "value_type": "dimensioned",
+                    "allowed": null,
+                    "unit": null,
+                    "range": null,
+                    "marginal": null,
+                    "outcome": "pass"
+                }
+            ],
+            "operator_inputs": []
+        }]
+    }]))
+    .unwrap();
+
+    let dir = common::TempDir::new("report");
+    let path = dir.join("measurements.csv");
+    CsvSink::new(path.to_str().unwrap()).write(&record).unwrap();
+    let csv = std::fs::read_to_string(&path).unwrap();
+
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "dut_id,suite,test,measurement,point,axis,value,raw_value,unit,low,high,mean,min,max,stddev,outcome",
+            "SN-0001,PowerSuite,rail_3v3,\"Rail, 3V3\",,,3.3,3300,V,3.2,3.4,,,,,PASS",
+            "SN-0001,PowerSuite,rail_3v3,Sweep,0,Frequency,10,,Hz,,,,,,,PASS",
+            "SN-0001,PowerSuite,rail_3v3,Sweep,0,Gain,1.5,,dB,,,,,,,PASS",
+            "SN-0001,PowerSuite,rail_3v3,Sweep,1,Frequency,20,,Hz,,,,,,,PASS",
+            "SN-0001,PowerSuite,rail_3v3,Sweep,1,Gain,0.5,,dB,,,,,,,PASS",
+        ]
+    );
+}
+
+/// A `<testcase>`'s attributes and its properties in order
+type JunitTestCase = (HashMap<String, String>, Vec<(String, String)>);
+
+/// Every `<testcase>` in a JUnit report
+fn junit_test_cases(xml: &str) -> Vec<JunitTestCase> {
+    let attributes = |tag: &BytesStart| -> HashMap<String, String> {
+        tag.attributes()
+            .map(|a| {
+                let a = a.unwrap();
+                let key = String::from_utf8(a.key.as_ref().to_vec()).unwrap();
+                (key, a.unescape_value().unwrap().into_owned())
+            })
+            .collect()
+    };
+
+    let mut reader = quick_xml::Reader::from_str(xml);
+    let mut cases = Vec::new();
+    let mut current = None;
+    loop {
+        match reader.read_event().unwrap() {
+            Event::Start(tag) if tag.name().as_ref() == b"testcase" => {
+                current = Some((attributes(&tag), Vec::new()));
+            }
+            Event::Empty(tag) if tag.name().as_ref() == b"testcase" => {
+                cases.push((attributes(&tag), Vec::new()));
+            }
+            Event::Empty(tag) if tag.name().as_ref() == b"property" => {
+                if let Some((_, properties)) = &mut current {
+                    let mut property = attributes(&tag);
+                    properties.push((
+                        property.remove("name").unwrap(),
+                        property.remove("value").unwrap(),
+                    ));
+                }
+            }
+            Event::End(tag) if tag.name().as_ref() == b"testcase" => {
+                cases.extend(current.take());
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+    }
+    cases
+}
+
+#[test]
+fn junit_test_cases_carry_measurements_and_locations() {
+    let mut record = record("SN-0001", "bench-3");
+    record.suites = serde_json::from_value(serde_json::json!([{
+        "name": "PowerSuite",
+        "priority": 0,
+        "start_time": "2025-01-01T12:00:00Z",
+        "outcome": "failed",
+        "lifecycle_errors": [],
+        "tests": [
+            {
+                "name": "rail_3v3",
+                "outcome": "failed",
+                "duration_secs": 0.5,
+                "failure": {
+                    "kind": "MeasurementFailed",
+                    "message": "Measurement 'Rail' - Expected: in range 3.2 to 3.4 - found 3.1",
+                    "file": "src/power.rs",
+                    "line": 42
+                },
+                "measurements": [{
+                    "name": "Rail",
+                    "value": 3.1,
+                    "raw_value": null,
+                    "statistics": null,
+                    "value_type": "f64",
+                    "allowed": null,
+                    "unit": "V",
+                    "range": { "min": 3.2, "max": 3.4 },
+                    "marginal": { "min": 3.25, "max": 3.35 },
+                    "outcome": "fail"
+                }],
+                "operator_inputs": []
+            },
+            {
+                "name": "fixture_closed",
+                "outcome": "passed",
+                "duration_secs": 0.1,
+                "failure": null,
+                "measurements": [],
+                "operator_inputs": []
+            }
+        ]
+    }]))
+    .unwrap();
+
+    let dir = common::TempDir::new("junit");
+    let path = dir.join("junit.xml");
+    JunitSink::new(path.to_str().unwrap())
+        .write(&record)
+        .unwrap();
+    let xml = std::fs::read_to_string(&path).unwrap();
+
+    let cases = junit_test_cases(&xml);
+    assert_eq!(cases.len(), 2);
+
+    let (attributes, properties) = &cases[0];
+    assert_eq!(attributes["name"], "rail_3v3");
+    assert_eq!(attributes["file"], "src/power.rs");
+    assert_eq!(attributes["line"], "42");
+    let properties: Vec<(&str, &str)> = properties
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(
+        properties,
+        [
+            ("measurement.Rail.value", "3.1"),
+            ("measurement.Rail.outcome", "FAIL"),
+            ("measurement.Rail.unit", "V"),
+            ("measurement.Rail.low", "3.2"),
+            ("measurement.Rail.high", "3.4"),
+            ("measurement.Rail.marginal_low", "3.25"),
+            ("measurement.Rail.marginal_high", "3.35"),
+        ]
+    );
+
+    let (attributes, properties) = &cases[1];
+    assert_eq!(attributes["name"], "fixture_closed");
+    assert!(!attributes.contains_key("file"));
+    assert!(properties.is_empty());
+}
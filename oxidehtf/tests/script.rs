@@ -0,0 +1,104 @@
+use oxidehtf::{ResponseScript, RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+const TOML: &str = r#"
+sequence = ["12", "B"]
+
+[answers]
+dut-serial = "SN-0001"
+"Is the fixture closed?" = "yes"
+"#;
+
+const JSON: &str = r#"{
+  "answers": { "dut-serial": "SN-0001", "Is the fixture closed?": "yes" },
+  "sequence": ["12", "B"]
+}"#;
+
+#[test]
+fn response_script_formats() {
+    let dir = common::TempDir::new("script");
+
+    for (name, contents) in [("responses.toml", TOML), ("responses.json", JSON)] {
+        let path = dir.join(name);
+        std::fs::write(&path, contents).unwrap();
+        assert!(ResponseScript::load(&path).is_ok());
+    }
+
+    let typo = dir.join("typo.toml");
+    std::fs::write(&typo, "answer = [\"yes\"]\n").unwrap();
+    assert!(ResponseScript::load(&typo).is_err());
+
+    assert!(ResponseScript::load(dir.join("missing.toml")).is_err());
+}
+
+struct ScriptedSuite;
+
+#[oxidehtf_macros::tests]
+impl ScriptedSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn answered_from_script(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        let serial = context
+            .text_input
+            .prompt("Enter the serial")
+            .id("dut-serial")
+            .request()?;
+        assert_eq!(serial, "SN-0001");
+
+        assert!(context.text_input.confirm("Is the fixture closed?")?);
+        assert_eq!(context.text_input.number("Screw count?", 0.0..)?, 12.0);
+        assert_eq!(context.text_input.request("Board revision?")?, "B");
+        Ok(())
+    }
+
+    #[test]
+    fn script_runs_out(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.text_input.request("Anything else?")?;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for ScriptedSuite {}
+
+#[test]
+fn script_answers_prompts() {
+    let dir = common::TempDir::new("script-run");
+
+    // The id beats the text, the text beats the sequence
+    let path = dir.join("responses.toml");
+    std::fs::write(
+        &path,
+        r#"
+sequence = ["12", "B"]
+
+[answers]
+dut-serial = "SN-0001"
+"Enter the serial" = "SN-WRONG"
+"Is the fixture closed?" = "yes"
+"#,
+    )
+    .unwrap();
+
+    let record = common::run(RunConfig::new().response_script(&path));
+
+    assert_eq!(
+        common::result(&record, "answered_from_script"),
+        (TestOutcome::Passed, None)
+    );
+    let inputs = &common::test(&record, "answered_from_script").operator_inputs;
+    let responses: Vec<_> = inputs.iter().map(|i| i.response.as_deref()).collect();
+    assert_eq!(
+        responses,
+        [Some("SN-0001"), Some("yes"), Some("12"), Some("B")]
+    );
+    assert!(inputs.iter().all(|i| i.scripted && !i.timed_out));
+
+    assert_eq!(
+        common::result(&record, "script_runs_out"),
+        (TestOutcome::Failed, Some("ScriptedAnswerFailed".into()))
+    );
+}
@@ -0,0 +1,73 @@
+use oxidehtf::{JunitSink, RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+struct Suite;
+
+#[oxidehtf_macros::tests]
+impl Suite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn skips_itself(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.skip("no radio on this variant")
+    }
+
+    #[test(skip = "fixture not fitted")]
+    fn skipped_by_attribute(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test]
+    fn skips_then_fails(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        context.skip("no radio on this variant")?;
+        oxidehtf::assert_eq!(1, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn fails(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(1, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn passes(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for Suite {}
+
+#[test]
+fn skipped_tests_are_not_failures() {
+    let dir = common::TempDir::new("skip");
+    let junit = dir.join("junit.xml");
+    let record = common::run(RunConfig::new().sink(JunitSink::new(junit.to_str().unwrap())));
+
+    let outcomes: Vec<TestOutcome> = record.suites[0].tests.iter().map(|t| t.outcome).collect();
+    assert_eq!(
+        outcomes,
+        [
+            TestOutcome::Skipped,
+            TestOutcome::Skipped,
+            TestOutcome::Failed,
+            TestOutcome::Failed,
+            TestOutcome::Passed
+        ]
+    );
+    assert_eq!(
+        record.suites[0].tests[0].skip_reason.as_deref(),
+        Some("no radio on this variant")
+    );
+    assert!(record.suites[0].tests[0].failure.is_none());
+
+    let xml = std::fs::read_to_string(&junit).unwrap();
+    let suite = xml.lines().find(|l| l.contains("<testsuite ")).unwrap();
+    assert!(suite.contains(r#"tests="5""#), "{}", suite);
+    assert!(suite.contains(r#"failures="2""#), "{}", suite);
+    assert!(suite.contains(r#"errors="0""#), "{}", suite);
+    assert!(suite.contains(r#"disabled="2""#), "{}", suite);
+}
@@ -0,0 +1,133 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use oxidehtf::{RunConfig, SysContext, TestFailure, TestLifecycle, TestOutcome};
+
+mod common;
+
+static FIXTURE_RELEASED: AtomicBool = AtomicBool::new(false);
+
+struct HangingSuite;
+
+#[oxidehtf_macros::tests(timeout = "2s")]
+impl HangingSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test(timeout = "100ms")]
+    async fn async_hang(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Ok(())
+    }
+
+    #[test]
+    fn within_suite_timeout(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+
+    #[test(timeout = "100ms")]
+    fn blocking_hang(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        std::thread::sleep(Duration::from_millis(300));
+        context.measurements.measure("Late").set(1.0)?;
+        Ok(())
+    }
+
+    #[test]
+    fn never_runs(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        Ok(())
+    }
+}
+
+impl TestLifecycle for HangingSuite {
+    fn teardown(&mut self) -> color_eyre::eyre::Result<()> {
+        FIXTURE_RELEASED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+struct AbandonedSuite;
+
+#[oxidehtf_macros::tests(1, abandon_on_timeout)]
+impl AbandonedSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn starts_after_teardown(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        oxidehtf::assert_eq!(FIXTURE_RELEASED.load(Ordering::SeqCst), true);
+        Ok(())
+    }
+
+    #[test(timeout = "100ms")]
+    fn abandoned_hang(&mut self, context: &mut SysContext) -> Result<(), TestFailure> {
+        std::thread::sleep(Duration::from_millis(300));
+        context.measurements.measure("Late").set(1.0)?;
+        Ok(())
+    }
+}
+
+impl TestLifecycle for AbandonedSuite {}
+
+struct NextSuite;
+
+#[oxidehtf_macros::tests(2)]
+impl NextSuite {
+    fn new() -> Self {
+        Self
+    }
+
+    #[test]
+    fn outlasts_the_hang(&mut self, _context: &mut SysContext) -> Result<(), TestFailure> {
+        // The abandoned test sets its measurement while this one runs
+        std::thread::sleep(Duration::from_millis(500));
+        Ok(())
+    }
+}
+
+impl TestLifecycle for NextSuite {}
+
+#[test]
+fn timed_out_tests_fail_and_their_writes_are_dropped() {
+    let record = common::run(RunConfig::new());
+
+    let timeout = (TestOutcome::Failed, Some("Timeout".to_string()));
+    assert_eq!(common::result(&record, "async_hang"), timeout);
+    assert_eq!(
+        common::result(&record, "within_suite_timeout"),
+        (TestOutcome::Passed, None)
+    );
+    assert_eq!(common::result(&record, "blocking_hang"), timeout);
+    assert_eq!(common::result(&record, "abandoned_hang"), timeout);
+    assert_eq!(
+        common::result(&record, "outlasts_the_hang"),
+        (TestOutcome::Passed, None)
+    );
+
+    // The blocked thread can't be stopped, so the rest of its suite isn't run,
+    // but the suite is torn down once the thread returns
+    assert_eq!(
+        common::test(&record, "never_runs").outcome,
+        TestOutcome::Skipped
+    );
+    assert!(record.suites[0].lifecycle_errors.is_empty());
+    assert_eq!(
+        common::result(&record, "starts_after_teardown"),
+        (TestOutcome::Passed, None)
+    );
+
+    // Unless the suite opts out of waiting for it
+    assert!(record.suites[1].lifecycle_errors[0]
+        .message
+        .contains("not run, test 'abandoned_hang' did not finish"));
+
+    let measurements = record
+        .suites
+        .iter()
+        .flat_map(|suite| &suite.tests)
+        .flat_map(|test| &test.measurements);
+    assert!(measurements.map(|m| &m.name).all(|name| name != "Late"));
+}